@@ -0,0 +1,239 @@
+//! 条件注册
+//!
+//! 通过 [`Condition`] 描述模块注册的条件，使同一套启动流程可以覆盖所有运行环境：
+//!
+//! ```ignore
+//! module_provider.register_if(Condition::profile("dev"), register_mock_mailer).await?;
+//! module_provider.register_if(Condition::module_missing::<Mailer>(), register_smtp_mailer).await?;
+//!
+//! module_provider.register_all(vec![
+//!     ModuleDefinition::new(register_redis_cache).when(Condition::config_present("redis")),
+//!     ModuleDefinition::new(register_memory_cache).when(Condition::module_missing::<RedisCache>()),
+//! ]).await?;
+//! ```
+//!
+//! 当前运行环境由 [`ModuleProvider::profile`] 决定。
+
+use std::any::type_name;
+use std::fmt;
+
+use config::{Config, Value};
+
+use crate::module::{display_name, ModuleKey, ModuleProvider};
+
+enum Predicate {
+    ConfigPresent(String),
+    ConfigEquals(String, String),
+    ConfigEnabled(String),
+    Profiles(Vec<String>),
+    Module {
+        key: ModuleKey,
+        type_name: &'static str,
+        present: bool,
+    },
+    Not(Box<Predicate>),
+    All(Vec<Predicate>),
+    Any(Vec<Predicate>),
+    Custom(String, Box<dyn Fn(&ModuleProvider) -> bool>),
+}
+
+/// 模块注册条件
+pub struct Condition(Predicate);
+
+impl Condition {
+    /// `Config` 模块中存在配置 `key`
+    pub fn config_present(key: &str) -> Self {
+        Condition(Predicate::ConfigPresent(key.to_string()))
+    }
+
+    /// `Config` 模块中配置 `key` 的值为 `value`
+    pub fn config_equals(key: &str, value: &str) -> Self {
+        Condition(Predicate::ConfigEquals(key.to_string(), value.to_string()))
+    }
+
+    /// `Config` 模块中配置 `key` 的值为 `true`，适用于功能开关
+    pub fn config_enabled(key: &str) -> Self {
+        Condition(Predicate::ConfigEnabled(key.to_string()))
+    }
+
+    /// 当前运行环境为 `profile`
+    pub fn profile(profile: &str) -> Self {
+        Self::profiles(&[profile])
+    }
+
+    /// 当前运行环境为 `profiles` 之一
+    pub fn profiles(profiles: &[&str]) -> Self {
+        Condition(Predicate::Profiles(profiles.iter().map(ToString::to_string).collect()))
+    }
+
+    /// 模块 `T` 已注册
+    pub fn module_present<T: 'static>() -> Self {
+        Condition(Predicate::Module { key: ModuleKey::of::<T>(), type_name: type_name::<T>(), present: true })
+    }
+
+    /// 具名模块 `T` 已注册
+    pub fn named_module_present<T: 'static>(name: &str) -> Self {
+        Condition(Predicate::Module { key: ModuleKey::named::<T>(name), type_name: type_name::<T>(), present: true })
+    }
+
+    /// 模块 `T` 未注册
+    pub fn module_missing<T: 'static>() -> Self {
+        Condition(Predicate::Module { key: ModuleKey::of::<T>(), type_name: type_name::<T>(), present: false })
+    }
+
+    /// 自定义条件，`description` 用于日志输出
+    pub fn custom<F>(description: &str, f: F) -> Self
+        where F: Fn(&ModuleProvider) -> bool + 'static
+    {
+        Condition(Predicate::Custom(description.to_string(), Box::new(f)))
+    }
+
+    /// 同时满足两个条件
+    pub fn and(self, other: Condition) -> Self {
+        match self.0 {
+            Predicate::All(mut predicates) => {
+                predicates.push(other.0);
+                Condition(Predicate::All(predicates))
+            }
+            predicate => Condition(Predicate::All(vec![predicate, other.0])),
+        }
+    }
+
+    /// 满足任一条件
+    pub fn or(self, other: Condition) -> Self {
+        match self.0 {
+            Predicate::Any(mut predicates) => {
+                predicates.push(other.0);
+                Condition(Predicate::Any(predicates))
+            }
+            predicate => Condition(Predicate::Any(vec![predicate, other.0])),
+        }
+    }
+
+    /// 不满足条件
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Condition(Predicate::Not(Box::new(self.0)))
+    }
+
+    /// 判断注册器当前的状态是否满足条件
+    pub fn matches(&self, provider: &ModuleProvider) -> bool {
+        self.0.matches(provider)
+    }
+
+    /// 条件中引用的模块，批量注册时保证这些模块先于当前模块注册
+    pub(crate) fn modules(&self) -> Vec<(ModuleKey, &'static str)> {
+        let mut modules = vec![];
+        self.0.collect_modules(&mut modules);
+        modules
+    }
+}
+
+impl Predicate {
+    fn matches(&self, provider: &ModuleProvider) -> bool {
+        let config = || provider.get_ref::<Config>();
+
+        match self {
+            Predicate::ConfigPresent(key) => config()
+                .map(|config| config.get::<Value>(key).is_ok())
+                .unwrap_or(false),
+            Predicate::ConfigEquals(key, value) => config()
+                .and_then(|config| config.get_str(key).ok())
+                .map(|current| &current == value)
+                .unwrap_or(false),
+            Predicate::ConfigEnabled(key) => config()
+                .and_then(|config| config.get_bool(key).ok())
+                .unwrap_or(false),
+            Predicate::Profiles(profiles) => provider.profile()
+                .map(|current| profiles.iter().any(|profile| current.is(profile)))
+                .unwrap_or(false),
+            Predicate::Module { key, present, .. } => provider.contains_key(key) == *present,
+            Predicate::Not(predicate) => !predicate.matches(provider),
+            Predicate::All(predicates) => predicates.iter().all(|predicate| predicate.matches(provider)),
+            Predicate::Any(predicates) => predicates.iter().any(|predicate| predicate.matches(provider)),
+            Predicate::Custom(_, f) => f(provider),
+        }
+    }
+
+    fn collect_modules(&self, modules: &mut Vec<(ModuleKey, &'static str)>) {
+        match self {
+            Predicate::Module { key, type_name, .. } => modules.push((key.clone(), type_name)),
+            Predicate::Not(predicate) => predicate.collect_modules(modules),
+            Predicate::All(predicates) | Predicate::Any(predicates) => {
+                predicates.iter().for_each(|predicate| predicate.collect_modules(modules))
+            }
+            _ => (),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |predicates: &[Predicate], separator: &str| predicates.iter()
+            .map(|predicate| format!("({})", predicate))
+            .collect::<Vec<_>>()
+            .join(separator);
+
+        match self {
+            Predicate::ConfigPresent(key) => write!(f, "config [{}] is present", key),
+            Predicate::ConfigEquals(key, value) => write!(f, "config [{}] is [{}]", key, value),
+            Predicate::ConfigEnabled(key) => write!(f, "config [{}] is enabled", key),
+            Predicate::Profiles(profiles) => write!(f, "profile in [{}]", profiles.join(", ")),
+            Predicate::Module { key, type_name, present } => write!(
+                f,
+                "module [{}] is {}",
+                display_name(type_name, key.name()),
+                if *present { "present" } else { "missing" }
+            ),
+            Predicate::Not(predicate) => write!(f, "not ({})", predicate),
+            Predicate::All(predicates) => f.write_str(&join(predicates, " and ")),
+            Predicate::Any(predicates) => f.write_str(&join(predicates, " or ")),
+            Predicate::Custom(description, _) => f.write_str(description),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Profile;
+
+    use super::*;
+
+    #[test]
+    fn test_conditions() {
+        let mut config = Config::new();
+        config.set("redis.connection", "redis://127.0.0.1").unwrap();
+        config.set("cache.driver", "redis").unwrap();
+        config.set("mail.enabled", true).unwrap();
+
+        let mut module_provider = ModuleProvider::initialize(config);
+        module_provider.insert(Profile::new("test"));
+        module_provider.insert(1u8);
+
+        assert!(Condition::config_present("redis.connection").matches(&module_provider));
+        assert!(!Condition::config_present("database").matches(&module_provider));
+        assert!(Condition::config_equals("cache.driver", "redis").matches(&module_provider));
+        assert!(Condition::config_enabled("mail.enabled").matches(&module_provider));
+        assert!(!Condition::config_enabled("sms.enabled").matches(&module_provider));
+        assert!(Condition::profiles(&["dev", "test"]).matches(&module_provider));
+        assert!(!Condition::profile("prod").matches(&module_provider));
+        assert!(Condition::module_present::<u8>().matches(&module_provider));
+        assert!(Condition::module_missing::<u16>().matches(&module_provider));
+        assert!(!Condition::named_module_present::<u8>("replica").matches(&module_provider));
+
+        let condition = Condition::profile("prod")
+            .or(Condition::module_present::<u8>().and(Condition::config_present("redis").not()));
+        assert!(!condition.matches(&module_provider));
+        assert_eq!(
+            "(profile in [prod]) or ((module [u8] is present) and (not (config [redis] is present)))",
+            condition.to_string()
+        );
+        assert_eq!(1, condition.modules().len());
+    }
+}
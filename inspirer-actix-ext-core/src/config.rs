@@ -1,14 +1,28 @@
 pub use config::*;
-use crate::module::ModuleProvider;
+use crate::error::BootstrapError;
+use crate::module::{display_name, ModuleDefinition, ModuleKey, ModuleProvider};
+use crate::placeholder;
+use crate::reload::Reloadable;
+use crate::task::{BackgroundTask, TaskContext};
+use futures::future::{Either, select};
+use futures_timer::Delay;
+use serde::de::DeserializeOwned;
+pub use validator::{Validate, ValidationError, ValidationErrors};
+use validator::ValidationErrorsKind;
+use std::any::type_name;
 use std::future::Future;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Clone)]
 pub enum ConfigProvider {
     Path(PathBuf),
     String(String),
+    /// 可选的配置文件，不存在时忽略，其余同 `String`
+    Optional(String),
     Env(String),
 }
 
@@ -17,41 +31,530 @@ impl fmt::Display for ConfigProvider {
         match self {
             ConfigProvider::Path(path_buf) => writeln!(f, "{:?}", path_buf),
             ConfigProvider::String(string) => writeln!(f, "{}", string),
+            ConfigProvider::Optional(string) => writeln!(f, "{} (optional)", string),
             ConfigProvider::Env(prefix) => writeln!(f, "Environment prefix = {}", prefix),
         }
     }
 }
 
-pub fn config_provider(config_providers: Vec<ConfigProvider>) -> impl Fn(&ModuleProvider) -> Pin<Box<dyn Future<Output=Result<Config, ConfigError>>>>
+/// 按顺序加载配置源并解析其中的 `${VAR}`、`${file:/path}` 占位符，语法见 [`crate::placeholder`]。
+pub fn config_provider(config_providers: Vec<ConfigProvider>) -> impl Fn(&ModuleProvider) -> Pin<Box<dyn Future<Output=Result<Config, ConfigError>>>> + Clone
 {
     move |_| {
         debug!("Register configuration manager module.");
         let config_files = config_providers.clone();
-        Box::pin(async move {
-            let mut config = Config::new();
-
-            for config_file in config_files.iter() {
-                debug!("Load config file: {}", config_file);
-                match config_file {
-                    ConfigProvider::Path(path) => config.merge(File::from(path.as_path())),
-                    ConfigProvider::String(name) => config.merge(File::with_name(name.as_str())),
-                    ConfigProvider::Env(prefix) => config.merge(Environment::with_prefix(prefix.as_str())),
-                }.map_err(|err| {
-                    error!("Load config file error: {}", err);
-                    err
-                })?;
-            }
-
-            Ok(config)
+        Box::pin(async move { load_config(&config_files) })
+    }
+}
+
+fn load_config(config_files: &[ConfigProvider]) -> Result<Config, ConfigError> {
+    let mut config = Config::new();
+
+    for config_file in config_files.iter() {
+        debug!("Load config file: {}", config_file);
+        match config_file {
+            ConfigProvider::Path(path) => config.merge(File::from(path.as_path())),
+            ConfigProvider::String(name) => config.merge(File::with_name(name.as_str())),
+            ConfigProvider::Optional(name) => config.merge(File::with_name(name.as_str()).required(false)),
+            ConfigProvider::Env(prefix) => config.merge(Environment::with_prefix(prefix.as_str())),
+        }.map_err(|err| {
+            error!("Load config file error: {}", err);
+            err
+        })?;
+    }
+
+    placeholder::resolve(&mut config).map_err(|err| {
+        error!("Resolve config placeholder error: {}", err);
+        err
+    })?;
+
+    Ok(config)
+}
+
+/// 配置文件扩展名，用于查找 `ConfigProvider::String` 等按名称指定的配置文件
+const CONFIG_EXTENSIONS: &[&str] = &["toml", "json", "yaml", "yml", "hjson", "ini"];
+
+type Fingerprint = Vec<Option<(SystemTime, u64)>>;
+
+/// 配置文件监听
+///
+/// 定期检查配置来源中文件的修改时间及大小，发生变化时按相同的来源重新加载配置：
+///
+/// ```ignore
+/// let config = module_provider.register_watched_config(
+///     ConfigWatcher::new(vec![ConfigProvider::Path("config/app.toml".into())])
+///         .interval(Duration::from_secs(2))
+/// ).await?;
+///
+/// module_provider.subscribe(|event: Arc<ConfigReloaded>, _| async move {
+///     let level = event.config.get_str("log.level")?;
+///     // 调整日志级别
+///     Ok(())
+/// });
+/// ```
+///
+/// `Config` 模块以可热替换模块的形式注册，重新加载成功后替换为新的实例，此后解析的 `Config` 依赖均为新的配置，
+/// 并发布 [`ConfigReloaded`] 事件；
+/// 加载失败（例如文件格式错误）时记录错误日志并保留之前的配置。
+/// 环境变量的变化不会触发重新加载，但会在重新加载时生效。
+#[derive(Debug, Clone)]
+pub struct ConfigWatcher {
+    providers: Vec<ConfigProvider>,
+    interval: Duration,
+}
+
+impl ConfigWatcher {
+    pub fn new(providers: Vec<ConfigProvider>) -> Self {
+        ConfigWatcher {
+            providers,
+            interval: Duration::from_secs(5),
+        }
+    }
+
+    /// 检查间隔，默认为 5 秒
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        self.providers.iter()
+            .flat_map(|provider| match provider {
+                ConfigProvider::Path(path) => vec![path.clone()],
+                ConfigProvider::String(name) | ConfigProvider::Optional(name) => {
+                    std::iter::once(PathBuf::from(name))
+                        .chain(CONFIG_EXTENSIONS.iter().map(|ext| PathBuf::from(format!("{}.{}", name, ext))))
+                        .collect()
+                }
+                ConfigProvider::Env(_) => vec![],
+            })
+            .collect()
+    }
+}
+
+/// 配置重新加载后发布的事件
+pub struct ConfigReloaded {
+    pub config: Arc<Config>,
+}
+
+struct ConfigWatch {
+    providers: Vec<ConfigProvider>,
+    files: Vec<PathBuf>,
+    interval: Duration,
+    fingerprint: Mutex<Fingerprint>,
+    config: Reloadable<Config>,
+}
+
+impl ConfigWatch {
+    fn fingerprint(files: &[PathBuf]) -> Fingerprint {
+        files.iter()
+            .map(|file| std::fs::metadata(file).ok()
+                .and_then(|metadata| metadata.modified().ok().map(|modified| (modified, metadata.len()))))
+            .collect()
+    }
+
+    async fn watch(&self, ctx: TaskContext) -> anyhow::Result<()> {
+        loop {
+            if let Either::Left(_) = select(Box::pin(ctx.cancelled()), Delay::new(self.interval)).await {
+                return Ok(());
+            }
+
+            let fingerprint = Self::fingerprint(&self.files);
+            {
+                let mut current = self.fingerprint.lock().unwrap();
+                if *current == fingerprint {
+                    continue;
+                }
+                *current = fingerprint;
+            }
+
+            match load_config(&self.providers) {
+                Ok(config) => {
+                    info!("Configuration changed, reloaded.");
+                    self.config.swap(config);
+
+                    if let Some(events) = ctx.container().events() {
+                        let _ = events.publish(ConfigReloaded { config: self.config.get() }).await;
+                    }
+                }
+                Err(err) => error!("Reload configuration failed, keep the previous configuration: {}", err),
+            }
+        }
+    }
+}
+
+/// 按运行环境分层的配置来源
+///
+/// 依次合并：`{dir}/default`、`{dir}/{profile}`、`{dir}/local` 配置文件（扩展名由 config 库识别），
+/// 最后是环境变量。运行环境读取自 `APP_PROFILE` 环境变量，除 `default` 外的配置文件均为可选，
+/// `local` 通常用于开发者本地覆盖且不纳入版本管理。
+///
+/// ```
+/// use inspirer_actix_ext_core::config::ConfigLayers;
+///
+/// let layers = ConfigLayers::new("config")
+///     .default_profile("dev")
+///     .env("APP");
+/// ```
+///
+/// 通过 [`ModuleProvider::register_config_layers`] 注册时，当前运行环境同时注册为 [`Profile`] 模块。
+#[derive(Debug, Clone)]
+pub struct ConfigLayers {
+    dir: PathBuf,
+    profile_var: String,
+    default_profile: Option<String>,
+    local: bool,
+    env_prefix: Option<String>,
+}
+
+impl ConfigLayers {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        ConfigLayers {
+            dir: dir.into(),
+            profile_var: "APP_PROFILE".into(),
+            default_profile: None,
+            local: true,
+            env_prefix: None,
+        }
+    }
+
+    /// 指定运行环境的环境变量名称，默认为 `APP_PROFILE`
+    pub fn profile_var(mut self, var: &str) -> Self {
+        self.profile_var = var.to_string();
+        self
+    }
+
+    /// 未设置环境变量时使用的运行环境，未指定时不加载运行环境配置
+    pub fn default_profile(mut self, profile: &str) -> Self {
+        self.default_profile = Some(profile.to_string());
+        self
+    }
+
+    /// 不加载 `local` 配置文件，例如在生产环境中
+    pub fn without_local(mut self) -> Self {
+        self.local = false;
+        self
+    }
+
+    /// 以 `prefix` 为前缀的环境变量覆盖配置
+    pub fn env(mut self, prefix: &str) -> Self {
+        self.env_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// 当前运行环境
+    pub fn profile(&self) -> Option<Profile> {
+        std::env::var(&self.profile_var).ok()
+            .filter(|profile| !profile.is_empty())
+            .or_else(|| self.default_profile.clone())
+            .map(Profile::new)
+    }
+
+    /// 按合并顺序排列的配置来源
+    pub fn providers(&self) -> Vec<ConfigProvider> {
+        let file = |name: &str| self.dir.join(name).to_string_lossy().into_owned();
+
+        let mut providers = vec![ConfigProvider::String(file("default"))];
+        if let Some(profile) = self.profile() {
+            providers.push(ConfigProvider::Optional(file(profile.name())));
+        }
+        if self.local {
+            providers.push(ConfigProvider::Optional(file("local")));
+        }
+        if let Some(prefix) = &self.env_prefix {
+            providers.push(ConfigProvider::Env(prefix.clone()));
+        }
+
+        providers
+    }
+}
+
+/// 运行环境，例如 `dev`、`test`、`prod`
+///
+/// 可以作为模块注册，用于按运行环境条件注册模块，参见 [`Condition::profile`](crate::condition::Condition::profile)。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile(String);
+
+impl Profile {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Profile(name.into())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is(&self, name: &str) -> bool {
+        self.0 == name
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// 类型化的配置节，通常通过 `#[derive(ConfigSection)]` 实现
+///
+/// ```ignore
+/// #[derive(Deserialize, Clone, ConfigSection)]
+/// #[config(key = "mail")]
+/// pub struct MailConfig {
+///     pub host: String,
+///     #[serde(default)]
+///     pub port: u16,
+/// }
+///
+/// module_provider.register_all(vec![config_definition, MailConfig::definition()]).await?;
+/// ```
+///
+/// 配置节以模块的形式注册，服务可以直接依赖 `MailConfig`。派生时指定 `#[config(key = "mail", default)]`
+/// 则在配置缺失时使用 `Default` 实现；指定 `validate` 则在注册时通过 [`Validate`] 校验配置节，
+/// 全部违反的规则连同配置键一并通过 [`BootstrapError::ConfigViolations`] 返回。
+pub trait ConfigSection: DeserializeOwned + Send + Sync + Clone + 'static {
+    /// 配置节的键
+    const KEY: &'static str;
+
+    /// 配置缺失时使用的配置，默认不提供
+    fn missing() -> Option<Self> {
+        None
+    }
+
+    /// 校验配置节，默认不校验
+    fn validate_section(&self) -> Result<(), ValidationErrors> {
+        Ok(())
+    }
+
+    /// 配置节模块定义，将保证 `Config` 模块先于配置节注册，并预检配置节
+    fn definition() -> ModuleDefinition {
+        ModuleDefinition::new(config_section_factory::<Self>)
+            .after::<Config>()
+            .config_check(check_config_section::<Self>)
+    }
+}
+
+/// 配置节模块工厂方法，读取并校验 `Config` 模块中的 `C::KEY` 配置节
+pub async fn config_section_factory<C: ConfigSection>(ctx: &ModuleProvider) -> Result<C, BootstrapError> {
+    ctx.load_config_section::<C>()
+}
+
+/// 校验配置节 `C`，适用于在注册任何模块之前集中校验全部配置节
+pub fn check_config_section<C: ConfigSection>(ctx: &ModuleProvider) -> Result<(), BootstrapError> {
+    ctx.load_config_section::<C>().map(drop)
+}
+
+impl ModuleDefinition {
+    /// 预检模块 `M` 所需的配置节 `C`，参见 [`ModuleDefinition::config_check`]
+    ///
+    /// 与 [`validated_config_section`](ModuleProvider::validated_config_section) 规则相同，
+    /// 但配置节缺失时不视为失败，交由工厂方法处理，以便配置节由其他模块提供。
+    pub fn validate_config<M, C>(self, key: &str) -> Self
+        where M: 'static,
+              C: ConfigSection
+    {
+        let key = key.to_string();
+        self.config_check(move |ctx| allow_missing(ctx.validated_config_section::<M, C>(&key)))
+    }
+
+    /// 预检具名模块 `M` 所需的配置节 `C`，参见 [`ModuleDefinition::validate_config`]
+    pub fn validate_named_config<M, C>(self, name: &str, key: &str) -> Self
+        where M: 'static,
+              C: ConfigSection
+    {
+        let (name, key) = (name.to_string(), key.to_string());
+        self.config_check(move |ctx| allow_missing(ctx.validated_named_config_section::<M, C>(&name, &key)))
+    }
+}
+
+fn allow_missing<C>(result: Result<C, BootstrapError>) -> Result<(), BootstrapError> {
+    match result {
+        Err(BootstrapError::ConfigMissing { .. }) => Ok(()),
+        result => result.map(drop),
+    }
+}
+
+/// 将校验错误展开为以配置键开头的违规描述
+fn violations(path: &str, errors: &ValidationErrors, output: &mut Vec<String>) {
+    for (field, kind) in errors.errors() {
+        let path = format!("{}.{}", path, field);
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                for error in errors {
+                    let message = error.message.as_ref().unwrap_or(&error.code);
+                    output.push(format!("[{}] {}", path, message));
+                }
+            }
+            ValidationErrorsKind::Struct(errors) => violations(&path, errors, output),
+            ValidationErrorsKind::List(errors) => {
+                for (index, errors) in errors {
+                    violations(&format!("{}[{}]", path, index), errors, output);
+                }
+            }
+        }
+    }
+}
+
+fn validate<C: ConfigSection>(module: String, key: &str, section: C) -> Result<C, BootstrapError> {
+    match section.validate_section() {
+        Ok(()) => Ok(section),
+        Err(errors) => {
+            let mut output = vec![];
+            violations(key, &errors, &mut output);
+            output.sort();
+            Err(BootstrapError::ConfigViolations { module, violations: output })
+        }
+    }
+}
+
+impl ModuleProvider {
+    /// 注册分层配置，同时将当前运行环境注册为 [`Profile`] 模块
+    pub async fn register_config_layers(&mut self, layers: ConfigLayers) -> Result<(), BootstrapError> {
+        let providers = self.activate_profile(&layers);
+        self.register(config_provider(providers)).await
+    }
+
+    /// 将分层配置的当前运行环境注册为 [`Profile`] 模块，返回各层配置来源
+    ///
+    /// 适用于需要在分层配置之后合并其他配置来源的场景。
+    pub fn activate_profile(&mut self, layers: &ConfigLayers) -> Vec<ConfigProvider> {
+        if let Some(profile) = layers.profile() {
+            info!("Active profile: {}", profile);
+            self.insert(profile);
+        }
+
+        layers.providers()
+    }
+
+    /// 注册配置节模块，参见 [`ConfigSection`]
+    pub async fn register_config_section<C: ConfigSection>(&mut self) -> Result<(), BootstrapError> {
+        self.register(config_section_factory::<C>).await
+    }
+
+    /// 注册可热加载的配置
+    ///
+    /// `Config` 模块通过 [`insert_reloadable`](ModuleProvider::insert_reloadable) 注册，
+    /// 并注册名为 `config-watcher` 的后台任务监听配置文件，详见 [`ConfigWatcher`]。
+    /// `Config` 模块已被覆盖时不加载配置，也不监听配置文件。
+    pub async fn register_watched_config(&mut self, watcher: ConfigWatcher) -> Result<Reloadable<Config>, BootstrapError> {
+        let files = watcher.files();
+        let fingerprint = ConfigWatch::fingerprint(&files);
+
+        let config = self.register_reloadable(config_provider(watcher.providers.clone())).await?;
+        if self.is_overridden(&ModuleKey::of::<Config>()) {
+            return Ok(config);
+        }
+
+        let watch = Arc::new(ConfigWatch {
+            providers: watcher.providers,
+            files,
+            interval: watcher.interval,
+            fingerprint: Mutex::new(fingerprint),
+            config: config.clone(),
+        });
+        self.register_task(BackgroundTask::new("config-watcher", move |ctx: TaskContext| {
+            let watch = watch.clone();
+            async move { watch.watch(ctx).await }
+        }));
+
+        Ok(config)
+    }
+
+    /// 当前运行环境
+    ///
+    /// 优先使用已注册的 [`Profile`] 模块，其次读取 `Config` 模块中的 `profile` 配置。
+    pub fn profile(&self) -> Option<Profile> {
+        self.get::<Profile>()
+            .or_else(|| self.get_ref::<Config>()
+                .and_then(|config| config.get_str("profile").ok())
+                .map(Profile::new))
+    }
+
+    /// 获取模块 `M` 所需的配置节
+    ///
+    /// 优先使用已注册的 `C` 模块，其次读取 `Config` 模块中的 `key` 配置节。
+    /// 配置缺失或无法解析时返回的错误中包含模块 `M` 及配置键。
+    pub fn config_section<M, C>(&self, key: &str) -> Result<C, BootstrapError>
+        where M: 'static,
+              C: DeserializeOwned + Send + Sync + Clone + 'static
+    {
+        match self.get::<C>() {
+            Some(section) => Ok(section),
+            None => {
+                debug!("Module provider is not contain <{}>, load config from <Config> module.", type_name::<C>());
+                self.load_section(type_name::<M>().to_string(), key)
+            }
+        }
+    }
+
+    /// 获取具名模块 `M` 所需的配置节，优先使用同名的 `C` 模块
+    pub fn named_config_section<M, C>(&self, name: &str, key: &str) -> Result<C, BootstrapError>
+        where M: 'static,
+              C: DeserializeOwned + Send + Sync + Clone + 'static
+    {
+        match self.get_named::<C>(name) {
+            Some(section) => Ok(section),
+            None => {
+                debug!("Module provider is not contain <{}> named [{}], load config from <Config> module.", type_name::<C>(), name);
+                self.load_section(display_name(type_name::<M>(), Some(name)), key)
+            }
+        }
+    }
+
+    /// 获取模块 `M` 所需的配置节并校验，参见 [`config_section`](ModuleProvider::config_section)
+    pub fn validated_config_section<M, C>(&self, key: &str) -> Result<C, BootstrapError>
+        where M: 'static,
+              C: ConfigSection
+    {
+        validate(type_name::<M>().to_string(), key, self.config_section::<M, C>(key)?)
+    }
+
+    /// 获取具名模块 `M` 所需的配置节并校验，参见 [`named_config_section`](ModuleProvider::named_config_section)
+    pub fn validated_named_config_section<M, C>(&self, name: &str, key: &str) -> Result<C, BootstrapError>
+        where M: 'static,
+              C: ConfigSection
+    {
+        validate(display_name(type_name::<M>(), Some(name)), key, self.named_config_section::<M, C>(name, key)?)
+    }
+
+    /// 读取并校验配置节 `C`，配置缺失时使用 [`ConfigSection::missing`]
+    pub fn load_config_section<C: ConfigSection>(&self) -> Result<C, BootstrapError> {
+        let module = type_name::<C>().to_string();
+        let section = match self.load_section::<C>(module.clone(), C::KEY) {
+            Err(BootstrapError::ConfigMissing { module, key }) => C::missing()
+                .ok_or(BootstrapError::ConfigMissing { module, key })?,
+            result => result?,
+        };
+
+        validate(module, C::KEY, section)
+    }
+
+    fn load_section<C: DeserializeOwned>(&self, module: String, key: &str) -> Result<C, BootstrapError> {
+        let config = self.get_ref::<Config>()
+            .ok_or_else(|| BootstrapError::ConfigMissing { module: module.clone(), key: key.to_string() })?;
+
+        config.get::<C>(key).map_err(|err| match err {
+            ConfigError::NotFound(_) => BootstrapError::ConfigMissing { module, key: key.to_string() },
+            source => BootstrapError::ConfigInvalid { module, key: key.to_string(), source: Box::new(source) },
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::module::ModuleProvider;
-    use crate::config::config_provider;
-    use config::Config;
+    use crate::module::{ModuleContainer, ModuleDefinition, ModuleProvider};
+    use crate::service::{IntoService, Service};
+    use actix_web::{App, test, web};
+    use crate::config::{check_config_section, config_provider, ConfigLayers, ConfigProvider, ConfigReloaded, ConfigSection, ConfigWatcher, Profile, ValidationErrors};
+    use crate::error::BootstrapError;
+    use config::{Config, ConfigError, File, FileFormat};
+    use futures::channel::mpsc;
+    use futures::StreamExt;
+    use futures_timer::Delay;
+    use serde::Deserialize;
+    use std::sync::Arc;
+    use validator::Validate;
+    use std::time::Duration;
 
     #[tokio::test]
     async fn test_provider() {
@@ -59,4 +562,302 @@ mod tests {
         assert!(module_provider.register(config_provider(vec![])).await.is_ok());
         assert!(module_provider.contains::<Config>());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_provider_placeholders() {
+        let path = crate::tests::temp_dir("config-placeholder").join("config.toml");
+        std::fs::write(&path, "[server]\nport = \"${CONFIG_PROVIDER_TEST_PORT:-8080}\"\nhost = \"${CONFIG_PROVIDER_TEST_HOST}\"\n").unwrap();
+        std::env::set_var("CONFIG_PROVIDER_TEST_HOST", "0.0.0.0");
+
+        let mut module_provider = ModuleProvider::new();
+        module_provider.register(config_provider(vec![ConfigProvider::Path(path.clone())])).await.unwrap();
+        let config = module_provider.get_ref::<Config>().unwrap();
+        assert_eq!(8080, config.get::<u16>("server.port").unwrap());
+        assert_eq!("0.0.0.0", config.get_str("server.host").unwrap());
+
+        std::env::remove_var("CONFIG_PROVIDER_TEST_HOST");
+        let err = ModuleProvider::new().register(config_provider(vec![ConfigProvider::Path(path)])).await.unwrap_err();
+        assert!(err.to_string().contains("Cannot resolve config [server.host]: environment variable [CONFIG_PROVIDER_TEST_HOST] is not set"));
+    }
+
+    #[derive(Deserialize, Clone)]
+    struct MailConfig {
+        host: String,
+    }
+
+    impl ConfigSection for MailConfig {
+        const KEY: &'static str = "mail";
+    }
+
+    #[derive(Deserialize, Clone, Default)]
+    struct RateLimitConfig {
+        #[serde(default)]
+        burst: u32,
+        rate: u32,
+    }
+
+    impl ConfigSection for RateLimitConfig {
+        const KEY: &'static str = "limit";
+
+        fn missing() -> Option<Self> {
+            Some(RateLimitConfig::default())
+        }
+    }
+
+    #[test]
+    fn test_profile() {
+        let mut config = Config::new();
+        config.set("profile", "prod").unwrap();
+
+        let mut module_provider = ModuleProvider::new();
+        assert!(module_provider.profile().is_none());
+
+        module_provider.insert(config);
+        assert_eq!(Some(Profile::new("prod")), module_provider.profile());
+
+        module_provider.insert(Profile::new("test"));
+        assert!(module_provider.profile().unwrap().is("test"));
+    }
+
+    #[test]
+    fn test_config_section() {
+        let mut config = Config::new();
+        config.set("mail.host", "smtp.example.com").unwrap();
+        config.set("mail.replica", 1).unwrap();
+
+        let mut module_provider = ModuleProvider::initialize(config);
+        let section = module_provider.config_section::<u8, MailConfig>("mail").unwrap();
+        assert_eq!("smtp.example.com", section.host);
+
+        let err = module_provider.config_section::<u8, MailConfig>("smtp").err().unwrap();
+        assert_eq!("Module [u8] requires config [smtp], but it is missing", err.to_string());
+
+        let err = module_provider.named_config_section::<u8, MailConfig>("replica", "mail.replica").err().unwrap();
+        assert!(err.to_string().starts_with("Module [u8(replica)] has invalid config [mail.replica]: "));
+
+        module_provider.insert_named("replica", MailConfig { host: "replica.example.com".into() });
+        let section = module_provider.named_config_section::<u8, MailConfig>("replica", "mail.replica").unwrap();
+        assert_eq!("replica.example.com", section.host);
+    }
+
+    #[tokio::test]
+    async fn test_register_config_section() {
+        let mut config = Config::new();
+        config.set("mail.host", "smtp.example.com").unwrap();
+        config.set("limit.rate", 100).unwrap();
+
+        let mut module_provider = ModuleProvider::new();
+        module_provider.register_all(vec![MailConfig::definition(), ModuleDefinition::new(move |_: &ModuleProvider| {
+            let config = config.clone();
+            async move { Ok::<_, ConfigError>(config) }
+        })]).await.unwrap();
+        module_provider.register_config_section::<RateLimitConfig>().await.unwrap();
+
+        assert_eq!("smtp.example.com", module_provider.get::<MailConfig>().unwrap().host);
+        let limit = module_provider.get::<RateLimitConfig>().unwrap();
+        assert_eq!((100, 0), (limit.rate, limit.burst));
+
+        let mut module_provider = ModuleProvider::new();
+        module_provider.register_config_section::<RateLimitConfig>().await.unwrap();
+        assert_eq!(0, module_provider.get::<RateLimitConfig>().unwrap().rate);
+
+        let err = module_provider.register_config_section::<MailConfig>().await.unwrap_err();
+        assert!(err.to_string().ends_with("requires config [mail], but it is missing"));
+    }
+
+    #[derive(Deserialize, Clone, Validate)]
+    struct UpstreamConfig {
+        #[validate(url)]
+        url: String,
+    }
+
+    #[derive(Deserialize, Clone, Validate)]
+    struct ProxyConfig {
+        #[validate(length(min = 1, message = "must not be empty"))]
+        host: String,
+        #[validate(range(min = 1))]
+        port: u16,
+        #[validate]
+        upstreams: Vec<UpstreamConfig>,
+    }
+
+    impl ConfigSection for ProxyConfig {
+        const KEY: &'static str = "proxy";
+
+        fn validate_section(&self) -> Result<(), ValidationErrors> {
+            self.validate()
+        }
+    }
+
+    #[test]
+    fn test_validate_config_section() {
+        let mut config = Config::new();
+        config.merge(File::from_str(
+            "[proxy]\nhost = \"\"\nport = 0\nupstreams = [{ url = \"http://127.0.0.1:8080\" }, { url = \"backend\" }]\n",
+            FileFormat::Toml,
+        )).unwrap();
+
+        let module_provider = ModuleProvider::initialize(config.clone());
+        let err = module_provider.load_config_section::<ProxyConfig>().err().unwrap();
+        match &err {
+            BootstrapError::ConfigViolations { violations, .. } => assert_eq!(
+                &vec!["[proxy.host] must not be empty", "[proxy.port] range", "[proxy.upstreams[1].url] url"],
+                violations
+            ),
+            _ => panic!("unexpected error: {}", err),
+        }
+        assert!(err.to_string().contains("has 3 config violation(s): [proxy.host] must not be empty; "));
+        assert!(check_config_section::<ProxyConfig>(&module_provider).is_err());
+
+        let err = module_provider.validated_named_config_section::<u8, ProxyConfig>("edge", "proxy").err().unwrap();
+        assert!(err.to_string().starts_with("Module [u8(edge)] has 3 config violation(s)"));
+
+        config.set("proxy.host", "0.0.0.0").unwrap();
+        config.set("proxy.port", 80).unwrap();
+        config.set("proxy.upstreams", Vec::<String>::new()).unwrap();
+        let module_provider = ModuleProvider::initialize(config);
+        assert_eq!(80, module_provider.validated_config_section::<u8, ProxyConfig>("proxy").unwrap().port);
+    }
+
+    #[tokio::test]
+    async fn test_register_all_config_checks() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CONNECTS: AtomicUsize = AtomicUsize::new(0);
+
+        let mut config = Config::new();
+        config.merge(File::from_str("[proxy]\nhost = \"\"\nport = 80\nupstreams = []\n[limit]\nrate = \"fast\"\n", FileFormat::Toml)).unwrap();
+
+        let definitions = || vec![
+            ModuleDefinition::new(|_: &ModuleProvider| async {
+                CONNECTS.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, ConfigError>(1u8)
+            }).validate_config::<u8, ProxyConfig>(ProxyConfig::KEY),
+            RateLimitConfig::definition(),
+            MailConfig::definition(),
+        ];
+
+        let mut module_provider = ModuleProvider::initialize(config.clone());
+        let err = module_provider.register_all(definitions()).await.unwrap_err();
+        let err_concurrent = module_provider.register_all_concurrent(definitions()).await.unwrap_err();
+
+        assert_eq!(0, CONNECTS.load(Ordering::SeqCst));
+        assert_eq!(err.to_string(), err_concurrent.to_string());
+        match err {
+            BootstrapError::ModulesInitFailed(failures) => {
+                assert_eq!(3, failures.len());
+                assert_eq!("Module [u8] has 1 config violation(s): [proxy.host] must not be empty", failures[0].to_string());
+                assert!(failures[1].to_string().contains("has invalid config [limit]"));
+                assert!(failures[2].to_string().ends_with("requires config [mail], but it is missing"));
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+
+        config.set("proxy.host", "0.0.0.0").unwrap();
+        config.set("limit.rate", 10).unwrap();
+        config.set("mail.host", "smtp.example.com").unwrap();
+        let mut module_provider = ModuleProvider::initialize(config);
+        module_provider.register_all(definitions()).await.unwrap();
+        assert_eq!(1, CONNECTS.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_config_layers() {
+        let dir = crate::tests::temp_dir("config-layers");
+        std::fs::write(dir.join("default.toml"), "name = \"app\"\nport = 8080\nlevel = \"info\"\n").unwrap();
+        std::fs::write(dir.join("prod.toml"), "port = 80\nlevel = \"warn\"\n").unwrap();
+        std::fs::write(dir.join("local.toml"), "level = \"debug\"\n").unwrap();
+
+        let layers = ConfigLayers::new(&dir).profile_var("CONFIG_LAYERS_TEST_PROFILE");
+        assert!(layers.profile().is_none());
+        assert_eq!(2, layers.providers().len());
+
+        std::env::set_var("CONFIG_LAYERS_TEST_PROFILE", "prod");
+        let mut module_provider = ModuleProvider::new();
+        module_provider.register_config_layers(layers).await.unwrap();
+
+        let config = module_provider.get_ref::<Config>().unwrap();
+        assert_eq!("app", config.get_str("name").unwrap());
+        assert_eq!(80, config.get_int("port").unwrap());
+        assert_eq!("debug", config.get_str("level").unwrap());
+        assert_eq!(Some(Profile::new("prod")), module_provider.profile());
+
+        let layers = ConfigLayers::new(&dir)
+            .profile_var("CONFIG_LAYERS_TEST_PROFILE")
+            .without_local();
+        let mut module_provider = ModuleProvider::new();
+        module_provider.register_config_layers(layers).await.unwrap();
+        assert_eq!("warn", module_provider.get_ref::<Config>().unwrap().get_str("level").unwrap());
+
+        let layers = ConfigLayers::new(dir.join("missing")).profile_var("CONFIG_LAYERS_TEST_PROFILE");
+        assert!(ModuleProvider::new().register_config_layers(layers).await.is_err());
+    }
+
+    struct RateLimiter(Config);
+
+    impl IntoService<(Config,)> for RateLimiter {
+        fn init(deps: (Config,)) -> Self {
+            RateLimiter(deps.0)
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_watched_config() {
+        let path = crate::tests::temp_dir("config-watch").join("config.toml");
+        std::fs::write(&path, "[limit]\nrate = 10\n").unwrap();
+
+        let (sender, mut receiver) = mpsc::unbounded();
+        let mut module_provider = ModuleProvider::new();
+        let config = module_provider.register_watched_config(
+            ConfigWatcher::new(vec![ConfigProvider::Path(path.clone())]).interval(Duration::from_millis(10))
+        ).await.unwrap();
+        module_provider.subscribe(move |event: Arc<ConfigReloaded>, _: ModuleContainer| {
+            let sender = sender.clone();
+            async move {
+                sender.unbounded_send(event.config.get_int("limit.rate")?)?;
+                Ok(())
+            }
+        });
+
+        let container = module_provider.into_module_container();
+        assert_eq!(10, container.reloadable::<Config>().unwrap().get().get_int("limit.rate").unwrap());
+        container.start_tasks();
+
+        std::fs::write(&path, "[limit]\nrate = 200\n").unwrap();
+        assert_eq!(Some(200), receiver.next().await);
+        assert_eq!(200, config.get().get_int("limit.rate").unwrap());
+        assert_eq!(200, container.get::<Config>().unwrap().get_int("limit.rate").unwrap());
+        let mut app = test::init_service(App::new().configure(container.module_provider())
+            .route("/", web::get().to(|srv: Service| async move {
+                let limiter = srv.get::<_, RateLimiter>()?;
+                Ok::<_, crate::error::Error>(limiter.0.get_int("limit.rate").unwrap().to_string())
+            }))).await;
+        assert_eq!("200", test::read_body(test::call_service(&mut app, test::TestRequest::get().uri("/").to_request()).await).await);
+
+        std::fs::write(&path, "[limit\nrate = ").unwrap();
+        Delay::new(Duration::from_millis(50)).await;
+        assert_eq!(200, config.get().get_int("limit.rate").unwrap());
+
+        std::fs::write(&path, "[limit]\nrate = 30\n").unwrap();
+        assert_eq!(Some(30), receiver.next().await);
+
+        container.shutdown().await;
+    }
+
+    #[actix_rt::test]
+    async fn test_watched_config_override() {
+        let mut fake = Config::new();
+        fake.set("limit.rate", 1).unwrap();
+
+        let mut module_provider = ModuleProvider::new();
+        module_provider.override_with(fake);
+        let config = module_provider.register_watched_config(
+            ConfigWatcher::new(vec![ConfigProvider::Path("/nonexistent/inspirer/config.toml".into())])
+        ).await.unwrap();
+
+        assert_eq!(1, config.get().get_int("limit.rate").unwrap());
+        let container = module_provider.into_module_container();
+        assert_eq!(1, container.get::<Config>().unwrap().get_int("limit.rate").unwrap());
+        assert!(container.tasks().is_empty());
+    }
+}
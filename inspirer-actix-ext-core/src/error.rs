@@ -1,10 +1,106 @@
+use std::any::type_name;
+use std::time::Duration;
+
 use thiserror::Error;
 use actix_web::ResponseError;
 
+use crate::module::display_name;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Cannot found dependency: {0}")]
     DependencyNotFound(&'static str),
+    #[error("Cannot found dependency: {0} named [{1}]")]
+    NamedDependencyNotFound(&'static str, &'static str),
+    #[error("Cannot initialize dependency: {module}, cause: {source}")]
+    DependencyInitFailed {
+        module: &'static str,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl ResponseError for Error {}
+
+/// 模块注册错误
+///
+/// 由 [`ModuleProvider`](crate::module::ModuleProvider) 的各注册方法返回，错误信息中包含出错的模块及配置键。
+/// 工厂方法可以直接返回该错误，此时注册器不会再次包装。
+#[derive(Error, Debug)]
+pub enum BootstrapError {
+    #[error("Module [{module}] depends on [{dependency}], but it is not registered")]
+    MissingDependency {
+        module: String,
+        dependency: String,
+    },
+    #[error("Circular module dependency: {0}")]
+    CircularDependency(String),
+    #[error("Module [{module}] requires config [{key}], but it is missing")]
+    ConfigMissing {
+        module: String,
+        key: String,
+    },
+    #[error("Module [{module}] has invalid config [{key}]: {source}")]
+    ConfigInvalid {
+        module: String,
+        key: String,
+        #[source]
+        source: Box<config::ConfigError>,
+    },
+    #[error("Module [{module}] has {} config violation(s): {}", .violations.len(), .violations.join("; "))]
+    ConfigViolations {
+        module: String,
+        /// 违反的校验规则，以配置键开头，例如 `[database.port] range`
+        violations: Vec<String>,
+    },
+    #[error("Module [{module}] factory failed: {source}")]
+    FactoryFailed {
+        module: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Module [{module}] factory timed out after {timeout:?}")]
+    Timeout {
+        module: String,
+        timeout: Duration,
+    },
+    #[error("Module [{module}] is not registered as reloadable")]
+    NotReloadable {
+        module: String,
+    },
+    #[error("{} module(s) failed to initialize: {}", .0.len(), join(.0))]
+    ModulesInitFailed(Vec<BootstrapError>),
 }
 
-impl ResponseError for Error {}
\ No newline at end of file
+fn join(errors: &[BootstrapError]) -> String {
+    errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
+impl BootstrapError {
+    /// 模块 `M` 的工厂方法执行失败
+    pub fn factory_failed<M, E>(source: E) -> Self
+        where M: 'static,
+              E: Into<Box<dyn std::error::Error + Send + Sync>>
+    {
+        BootstrapError::FactoryFailed { module: type_name::<M>().to_string(), source: source.into() }
+    }
+
+    /// 具名模块 `M` 的工厂方法执行失败
+    pub fn named_factory_failed<M, E>(name: &str, source: E) -> Self
+        where M: 'static,
+              E: Into<Box<dyn std::error::Error + Send + Sync>>
+    {
+        BootstrapError::FactoryFailed { module: display_name(type_name::<M>(), Some(name)), source: source.into() }
+    }
+
+    /// 将工厂方法返回的错误转换为注册错误，已经是注册错误时保持不变
+    pub(crate) fn from_factory<E>(module: String, err: E) -> Self
+        where E: std::error::Error + Send + Sync + 'static
+    {
+        let source: Box<dyn std::error::Error + Send + Sync> = Box::new(err);
+        match source.downcast::<BootstrapError>() {
+            Ok(err) => *err,
+            Err(source) => BootstrapError::FactoryFailed { module, source },
+        }
+    }
+}
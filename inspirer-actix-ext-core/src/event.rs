@@ -0,0 +1,361 @@
+//! 进程内事件总线
+//!
+//! 事件处理器在启动时通过 [`ModuleProvider::subscribe`] 订阅，服务通过注入的 [`Events`] 发布事件：
+//!
+//! ```ignore
+//! #[derive(Debug)]
+//! pub struct UserCreated { pub id: u64 }
+//!
+//! module_provider.subscribe(|event: Arc<UserCreated>, container: ModuleContainer| async move {
+//!     let mailer = container.get::<Mailer>().unwrap();
+//!     mailer.welcome(event.id).await
+//! });
+//!
+//! pub struct UserService(MySqlPool, Events);
+//!
+//! impl UserService {
+//!     pub async fn create(&self) -> anyhow::Result<()> {
+//!         let id = insert_user(&self.0).await?;
+//!         self.1.publish(UserCreated { id }).await?;
+//!         Ok(())
+//!     }
+//! }
+//! ```
+//!
+//! [`Events::publish`] 按订阅顺序执行处理器并等待完成，[`Events::emit`] 则在后台执行。
+//! 单个处理器返回错误或 panic 不会影响其他处理器。需要在事务提交后才分发的事件可以通过
+//! [`Events::deferred`] 暂存，数据库模块据此提供了提交后自动分发的事务。
+
+use std::any::{Any, type_name, TypeId};
+use std::fmt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, RwLock};
+
+use ahash::AHashMap;
+use actix_web::rt;
+use futures::future::{FutureExt, LocalBoxFuture};
+
+use crate::error::Error;
+use crate::module::{ModuleContainer, ModuleProvider};
+use crate::service::{Dependency, ResolveContext};
+use crate::task::panic_message;
+
+type SharedEvent = Arc<dyn Any + Send + Sync>;
+type HandlerFn = Arc<dyn Fn(SharedEvent, ModuleContainer) -> LocalBoxFuture<'static, anyhow::Result<()>> + Send + Sync>;
+
+#[derive(Clone)]
+struct Handler {
+    name: &'static str,
+    run: HandlerFn,
+}
+
+/// 事件总线，保存各类型事件的处理器
+#[derive(Clone, Default)]
+pub struct EventBus {
+    handlers: Arc<RwLock<AHashMap<TypeId, Vec<Handler>>>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 订阅事件 `E`
+    pub fn subscribe<E, F, R>(&self, handler: F)
+        where E: Send + Sync + 'static,
+              F: Fn(Arc<E>, ModuleContainer) -> R + Send + Sync + 'static,
+              R: Future<Output=anyhow::Result<()>> + 'static
+    {
+        debug!("Subscribe event [{}] with [{}].", type_name::<E>(), type_name::<F>());
+        let run: HandlerFn = Arc::new(move |event: SharedEvent, container| {
+            match event.downcast::<E>() {
+                Ok(event) => handler(event, container).boxed_local(),
+                Err(_) => unreachable!("event handlers are indexed by event type"),
+            }
+        });
+
+        self.handlers.write().unwrap()
+            .entry(TypeId::of::<E>())
+            .or_default()
+            .push(Handler { name: type_name::<F>(), run });
+    }
+
+    /// 是否存在事件 `E` 的处理器
+    pub fn has_subscribers<E: 'static>(&self) -> bool {
+        self.handlers.read().unwrap().contains_key(&TypeId::of::<E>())
+    }
+
+    fn handlers(&self, event: TypeId) -> Vec<Handler> {
+        self.handlers.read().unwrap().get(&event).cloned().unwrap_or_default()
+    }
+}
+
+/// 事件处理失败
+#[derive(Debug)]
+pub struct EventError {
+    pub event: &'static str,
+    /// 失败的处理器及其错误信息
+    pub failures: Vec<(&'static str, String)>,
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} handler(s) of event [{}] failed: ", self.failures.len(), self.event)?;
+        let failures = self.failures.iter()
+            .map(|(handler, err)| format!("[{}] {}", handler, err))
+            .collect::<Vec<_>>();
+        f.write_str(&failures.join("; "))
+    }
+}
+
+impl std::error::Error for EventError {}
+
+struct QueuedEvent {
+    type_id: TypeId,
+    name: &'static str,
+    event: SharedEvent,
+}
+
+impl QueuedEvent {
+    fn new<E: Send + Sync + 'static>(event: E) -> Self {
+        QueuedEvent { type_id: TypeId::of::<E>(), name: type_name::<E>(), event: Arc::new(event) }
+    }
+}
+
+async fn dispatch(bus: &EventBus, container: &ModuleContainer, queued: QueuedEvent) -> Result<(), EventError> {
+    let mut failures = vec![];
+
+    for handler in bus.handlers(queued.type_id) {
+        let result = AssertUnwindSafe(async { (handler.run)(queued.event.clone(), container.clone()).await })
+            .catch_unwind()
+            .await;
+
+        let err = match result {
+            Ok(Ok(())) => continue,
+            Ok(Err(err)) => format!("{:#}", err),
+            Err(panic) => format!("panicked: {}", panic_message(panic.as_ref())),
+        };
+
+        error!("Handler [{}] of event [{}] failed: {}", handler.name, queued.name, err);
+        failures.push((handler.name, err));
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(EventError { event: queued.name, failures })
+    }
+}
+
+/// 事件发布者
+///
+/// 可作为服务的依赖注入，在请求之外通过 [`ModuleContainer::events`] 获取。
+pub struct Events {
+    bus: EventBus,
+    container: ModuleContainer,
+}
+
+impl Events {
+    /// 同步分发：按订阅顺序执行事件处理器并等待全部完成
+    ///
+    /// 所有处理器均会执行，存在失败的处理器时返回 [`EventError`]。
+    pub async fn publish<E>(&self, event: E) -> Result<(), EventError>
+        where E: Send + Sync + 'static
+    {
+        dispatch(&self.bus, &self.container, QueuedEvent::new(event)).await
+    }
+
+    /// 异步分发：在后台执行事件处理器，失败仅记录日志
+    ///
+    /// 需要在 actix 运行时中调用。
+    pub fn emit<E>(&self, event: E)
+        where E: Send + Sync + 'static
+    {
+        let bus = self.bus.clone();
+        let container = self.container.clone();
+        rt::spawn(async move {
+            let _ = dispatch(&bus, &container, QueuedEvent::new(event)).await;
+        });
+    }
+
+    /// 创建暂存事件的队列，用于在事务提交后再分发
+    pub fn deferred(&self) -> DeferredEvents {
+        DeferredEvents {
+            bus: self.bus.clone(),
+            container: self.container.clone(),
+            queue: vec![],
+        }
+    }
+}
+
+impl Dependency for Events {
+    fn resolve(ctx: ResolveContext<'_>) -> Result<Self, Error> {
+        ctx.container()
+            .and_then(|container| container.events())
+            .ok_or(Error::DependencyNotFound(type_name::<EventBus>()))
+    }
+}
+
+/// 暂存的事件
+///
+/// 调用 [`DeferredEvents::publish`] 时按暂存顺序分发，直接丢弃时暂存的事件不会分发。
+pub struct DeferredEvents {
+    bus: EventBus,
+    container: ModuleContainer,
+    queue: Vec<QueuedEvent>,
+}
+
+impl DeferredEvents {
+    pub fn push<E>(&mut self, event: E)
+        where E: Send + Sync + 'static
+    {
+        self.queue.push(QueuedEvent::new(event));
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// 依次同步分发暂存的事件，返回各事件的处理失败
+    pub async fn publish(self) -> Vec<EventError> {
+        let mut errors = vec![];
+        for queued in self.queue {
+            if let Err(err) = dispatch(&self.bus, &self.container, queued).await {
+                errors.push(err);
+            }
+        }
+
+        errors
+    }
+}
+
+impl ModuleProvider {
+    /// 订阅事件 `E`，首次订阅时注册 [`EventBus`] 模块
+    pub fn subscribe<E, F, R>(&mut self, handler: F)
+        where E: Send + Sync + 'static,
+              F: Fn(Arc<E>, ModuleContainer) -> R + Send + Sync + 'static,
+              R: Future<Output=anyhow::Result<()>> + 'static
+    {
+        let bus = match self.get::<EventBus>() {
+            Some(bus) => bus,
+            None => {
+                let bus = EventBus::new();
+                self.insert(bus.clone());
+                bus
+            }
+        };
+
+        bus.subscribe(handler);
+    }
+}
+
+impl ModuleContainer {
+    /// 事件发布者，未注册 [`EventBus`] 模块时不存在
+    pub fn events(&self) -> Option<Events> {
+        self.get::<EventBus>().map(|bus| Events { bus, container: self.clone() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use futures::channel::mpsc;
+    use futures::StreamExt;
+
+    use crate::service::IntoService;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct UserCreated(u64);
+
+    struct UserService(Events);
+
+    impl IntoService<(Events,)> for UserService {
+        fn init(deps: (Events,)) -> Self {
+            UserService(deps.0)
+        }
+    }
+
+    fn recorder(module_provider: &mut ModuleProvider) -> Arc<Mutex<Vec<u64>>> {
+        let received = Arc::new(Mutex::new(vec![]));
+        let handler_received = received.clone();
+        module_provider.subscribe(move |event: Arc<UserCreated>, container: ModuleContainer| {
+            let received = handler_received.clone();
+            async move {
+                assert_eq!(Some("mailer".to_string()), container.get::<String>());
+                received.lock().unwrap().push(event.0);
+                Ok(())
+            }
+        });
+
+        received
+    }
+
+    #[actix_rt::test]
+    async fn test_publish() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert("mailer".to_string());
+        module_provider.subscribe(|_: Arc<UserCreated>, _| async { anyhow::bail!("smtp unavailable") });
+        module_provider.subscribe(|_: Arc<UserCreated>, _| async { panic!("handler panicked") });
+        let received = recorder(&mut module_provider);
+        module_provider.subscribe(|_: Arc<String>, _| async { panic!("unrelated handler") });
+
+        let container = module_provider.into_module_container();
+        let user_service = container.service::<_, UserService>().unwrap();
+
+        let err = user_service.0.publish(UserCreated(1)).await.unwrap_err();
+        assert_eq!(2, err.failures.len());
+        assert_eq!("smtp unavailable", err.failures[0].1);
+        assert_eq!("panicked: handler panicked", err.failures[1].1);
+        assert_eq!(vec![1], *received.lock().unwrap());
+
+        assert!(ModuleProvider::new().into_module_container().service::<_, UserService>().is_err());
+    }
+
+    #[actix_rt::test]
+    async fn test_emit() {
+        let (sender, mut receiver) = mpsc::unbounded();
+        let mut module_provider = ModuleProvider::new();
+        module_provider.subscribe(move |event: Arc<UserCreated>, _| {
+            let sender = sender.clone();
+            async move {
+                sender.unbounded_send(event.0)?;
+                Ok(())
+            }
+        });
+
+        let events = module_provider.into_module_container().events().unwrap();
+        events.emit(UserCreated(2));
+
+        assert_eq!(Some(2), receiver.next().await);
+    }
+
+    #[actix_rt::test]
+    async fn test_deferred() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert("mailer".to_string());
+        let received = recorder(&mut module_provider);
+        let events = module_provider.into_module_container().events().unwrap();
+
+        let mut deferred = events.deferred();
+        deferred.push(UserCreated(3));
+        deferred.push(UserCreated(4));
+        assert_eq!(2, deferred.len());
+        assert!(received.lock().unwrap().is_empty());
+
+        assert!(deferred.publish().await.is_empty());
+        assert_eq!(vec![3, 4], *received.lock().unwrap());
+
+        let mut discarded = events.deferred();
+        discarded.push(UserCreated(5));
+        drop(discarded);
+        assert_eq!(vec![3, 4], *received.lock().unwrap());
+    }
+}
@@ -0,0 +1,199 @@
+//! 功能模块
+//!
+//! 普通模块只向应用注册数据，而功能模块可以同时提供应用数据与一组路由，
+//! 适用于以独立 crate 复用的功能，例如认证、后台管理等：
+//!
+//! ```
+//! use actix_web::{web, HttpResponse, Scope};
+//! use actix_web::web::ServiceConfig;
+//! use inspirer_actix_ext_core::feature::FeatureModule;
+//!
+//! struct AdminModule;
+//!
+//! impl FeatureModule for AdminModule {
+//!     fn name(&self) -> &'static str {
+//!         "admin"
+//!     }
+//!
+//!     fn prefix(&self) -> &str {
+//!         "/admin"
+//!     }
+//!
+//!     fn routes(&self, config: &mut ServiceConfig) {
+//!         config.route("/dashboard", web::get().to(|| HttpResponse::Ok()));
+//!     }
+//!
+//!     fn scope(&self, scope: Scope) -> Scope {
+//!         scope.default_service(web::to(|| HttpResponse::NotFound()))
+//!     }
+//! }
+//! ```
+//!
+//! 通过 [`ModuleProvider::insert_feature`] 注册后，功能模块将随 [`ModuleContainer::module_provider`]
+//! 一同挂载到应用中。若注册器中存在 `Config` 模块，将读取其中的 `modules.{name}` 配置节：
+//!
+//! ```toml
+//! [modules.admin]
+//! # 是否启用，默认启用
+//! enabled = true
+//! # 覆盖默认的挂载前缀
+//! prefix = "/console"
+//! ```
+//!
+//! [`ModuleContainer::module_provider`]: crate::module::ModuleContainer::module_provider
+
+use std::any::{Any, type_name};
+
+use actix_web::{Scope, web};
+use actix_web::web::ServiceConfig;
+use config::{Config, ConfigError};
+use serde::Deserialize;
+
+use crate::module::ModuleRegister;
+
+/// 功能模块 trait
+pub trait FeatureModule: Send + Sync + 'static {
+    /// 功能模块名称，用于读取 `modules.{name}` 配置节
+    fn name(&self) -> &'static str;
+
+    /// 默认挂载前缀，为空时路由直接注册在应用根路径下
+    fn prefix(&self) -> &str {
+        ""
+    }
+
+    /// 注册应用数据，对整个应用生效
+    fn data(&self, _config: &mut ServiceConfig) {}
+
+    /// 注册路由，挂载在前缀下
+    fn routes(&self, config: &mut ServiceConfig);
+
+    /// 定制挂载的 Scope，例如添加守卫或默认服务
+    ///
+    /// 挂载前缀为空时路由直接注册在应用中，不会调用该方法。
+    fn scope(&self, scope: Scope) -> Scope {
+        scope
+    }
+}
+
+/// 功能模块配置
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeatureConfig {
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    pub prefix: Option<String>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for FeatureConfig {
+    fn default() -> Self {
+        FeatureConfig { enabled: true, prefix: None }
+    }
+}
+
+impl FeatureConfig {
+    /// 从配置中读取 `modules.{name}` 配置节，不存在时使用默认配置
+    pub fn load(config: &Config, name: &str) -> Result<Self, ConfigError> {
+        match config.get::<FeatureConfig>(&format!("modules.{}", name)) {
+            Err(ConfigError::NotFound(_)) => Ok(FeatureConfig::default()),
+            result => result,
+        }
+    }
+}
+
+pub(crate) struct FeatureModuleRegister<F> {
+    pub feature: F,
+    pub prefix: String,
+}
+
+impl<F: FeatureModule> ModuleRegister for FeatureModuleRegister<F> {
+    fn register(&self, service: &mut ServiceConfig) {
+        self.feature.data(service);
+
+        if self.prefix.is_empty() || self.prefix == "/" {
+            self.feature.routes(service);
+        } else {
+            let scope = web::scope(&self.prefix).configure(|config| self.feature.routes(config));
+            service.service(self.feature.scope(scope));
+        }
+    }
+
+    fn get_module(&self) -> Box<dyn Any> {
+        Box::new(())
+    }
+
+    fn module_name(&self) -> &'static str {
+        type_name::<F>()
+    }
+
+    fn module_qualifier(&self) -> Option<&str> {
+        Some(self.feature.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, HttpResponse, test};
+    use actix_web::http::StatusCode;
+    use actix_web::web::Bytes;
+
+    use crate::inspect::ModuleKind;
+    use crate::module::ModuleProvider;
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct Greeting(&'static str);
+
+    struct HelloModule(&'static str);
+
+    impl FeatureModule for HelloModule {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn prefix(&self) -> &str {
+            "/hello"
+        }
+
+        fn data(&self, config: &mut ServiceConfig) {
+            config.data(Greeting("hello"));
+        }
+
+        fn routes(&self, config: &mut ServiceConfig) {
+            config.route("/world", web::get().to(|greeting: web::Data<Greeting>| {
+                HttpResponse::Ok().body(format!("{} world", greeting.0))
+            }));
+        }
+
+        fn scope(&self, scope: Scope) -> Scope {
+            scope.default_service(web::to(HttpResponse::Gone))
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_feature_module() {
+        let mut config = Config::new();
+        config.set("modules.console.prefix", "/console").unwrap();
+        config.set("modules.disabled.enabled", false).unwrap();
+
+        let mut module_provider = ModuleProvider::initialize(config);
+        module_provider.insert_feature(HelloModule("hello")).unwrap();
+        module_provider.insert_feature(HelloModule("console")).unwrap();
+        module_provider.insert_feature(HelloModule("disabled")).unwrap();
+
+        let container = module_provider.into_module_container();
+        assert_eq!(3, container.modules().len());
+        assert_eq!(ModuleKind::Feature, container.modules()[1].kind);
+        assert_eq!("console", container.modules()[2].name.as_deref().unwrap());
+
+        let mut app = test::init_service(App::new().configure(container.module_provider())).await;
+        let call = |uri: &'static str| test::TestRequest::get().uri(uri).to_request();
+
+        assert_eq!(Bytes::from_static(b"hello world"), test::read_body(test::call_service(&mut app, call("/hello/world")).await).await);
+        assert_eq!(StatusCode::OK, test::call_service(&mut app, call("/console/world")).await.status());
+        assert_eq!(StatusCode::GONE, test::call_service(&mut app, call("/console/other")).await.status());
+    }
+}
@@ -0,0 +1,221 @@
+//! 模块健康检查
+//!
+//! 模块可通过 [`ModuleHooks::liveness`](crate::lifecycle::ModuleHooks::liveness) 及
+//! [`ModuleHooks::readiness`](crate::lifecycle::ModuleHooks::readiness) 提供存活与就绪探针，
+//! 由 [`ModuleContainer`] 汇总为健康报告，并可直接挂载为 actix web 服务：
+//!
+//! ```ignore
+//! let container = module_provider.into_module_container();
+//!
+//! HttpServer::new(move || {
+//!     App::new()
+//!         .configure(container.module_provider())
+//!         .service(container.health_service("/health"))
+//! })
+//! ```
+//!
+//! 挂载后 `/health/live` 与 `/health/ready` 将返回各模块的状态及探测耗时，
+//! 全部正常时响应 `200 OK`，否则响应 `503 Service Unavailable`。
+//!
+//! 各探针并发执行，超过超时时间（默认为 [`DEFAULT_PROBE_TIMEOUT`]，可通过
+//! [`ModuleHooks::probe_timeout`](crate::lifecycle::ModuleHooks::probe_timeout) 指定）未完成的探针视为失败，
+//! 因此单个模块无响应不会阻塞整个健康检查。
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use actix_web::{HttpResponse, Scope, web};
+use futures::future::{Either, join_all, LocalBoxFuture, select};
+use futures_timer::Delay;
+use serde::Serialize;
+
+use crate::module::{display_name, ModuleContainer, ModuleRegister};
+
+/// 健康状态
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+/// 单个模块的健康检查结果
+#[derive(Serialize, Debug, Clone)]
+pub struct ModuleHealth {
+    pub module: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub status: HealthStatus,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 健康报告
+///
+/// 所有模块均正常时整体状态为 `Up`，未提供探针的模块不会出现在报告中。
+#[derive(Serialize, Debug, Clone)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub modules: Vec<ModuleHealth>,
+}
+
+impl HealthReport {
+    pub fn is_up(&self) -> bool {
+        self.status == HealthStatus::Up
+    }
+
+    fn into_response(self) -> HttpResponse {
+        if self.is_up() {
+            HttpResponse::Ok().json(self)
+        } else {
+            HttpResponse::ServiceUnavailable().json(self)
+        }
+    }
+}
+
+/// 探针的默认超时时间
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn probe(register: &dyn ModuleRegister, probe: LocalBoxFuture<'static, anyhow::Result<()>>) -> ModuleHealth {
+    let timeout = register.probe_timeout().unwrap_or(DEFAULT_PROBE_TIMEOUT);
+    let start = Instant::now();
+    let result = match select(probe, Delay::new(timeout)).await {
+        Either::Left((result, _)) => result,
+        Either::Right(_) => Err(anyhow::anyhow!("timed out after {:?}", timeout)),
+    };
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    let mut health = ModuleHealth {
+        module: register.module_name(),
+        name: register.module_qualifier().map(ToString::to_string),
+        status: HealthStatus::Up,
+        latency_ms,
+        error: None,
+    };
+
+    if let Err(err) = result {
+        warn!("Module [{}] health check failed: {:?}", display_name(health.module, health.name.as_deref()), err);
+        health.status = HealthStatus::Down;
+        health.error = Some(format!("{:#}", err));
+    }
+
+    health
+}
+
+async fn check<F>(registers: &[Arc<dyn ModuleRegister>], select: F) -> HealthReport
+    where F: Fn(&dyn ModuleRegister) -> Option<LocalBoxFuture<'static, anyhow::Result<()>>>
+{
+    let probes = registers.iter()
+        .filter_map(|register| select(register.as_ref()).map(|future| probe(register.as_ref(), future)));
+
+    let modules = join_all(probes).await;
+    let status = if modules.iter().all(|module| module.status == HealthStatus::Up) {
+        HealthStatus::Up
+    } else {
+        HealthStatus::Down
+    };
+
+    HealthReport { status, modules }
+}
+
+impl ModuleContainer {
+    /// 执行所有模块的存活探针
+    pub async fn liveness(&self) -> HealthReport {
+        check(self.registers(), |register| register.liveness_probe()).await
+    }
+
+    /// 执行所有模块的就绪探针
+    pub async fn readiness(&self) -> HealthReport {
+        check(self.registers(), |register| register.readiness_probe()).await
+    }
+
+    /// 健康检查服务
+    ///
+    /// 在指定路径下提供 `/live` 与 `/ready` 两个端点，可直接作为 Kubernetes 探针使用。
+    pub fn health_service(&self, path: &str) -> Scope {
+        web::scope(path)
+            .data(self.clone())
+            .route("/live", web::get().to(liveness_handler))
+            .route("/ready", web::get().to(readiness_handler))
+    }
+}
+
+async fn liveness_handler(container: web::Data<ModuleContainer>) -> HttpResponse {
+    container.liveness().await.into_response()
+}
+
+async fn readiness_handler(container: web::Data<ModuleContainer>) -> HttpResponse {
+    container.readiness().await.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, test};
+    use actix_web::http::StatusCode;
+    use serde_json::Value;
+
+    use crate::lifecycle::ModuleHooks;
+    use crate::module::ModuleProvider;
+
+    use super::*;
+
+    fn container() -> ModuleContainer {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert(0u8);
+        module_provider.insert_with_hooks(
+            1u16,
+            ModuleHooks::new()
+                .liveness(|_| async { Ok(()) })
+                .readiness(|_| async { Ok(()) }),
+        );
+        module_provider.insert_with_hooks(
+            2u32,
+            ModuleHooks::new().readiness(|_| async { Err(anyhow::anyhow!("connection refused")) }),
+        );
+        module_provider.insert_with_hooks(
+            3u64,
+            ModuleHooks::new()
+                .readiness(|_| futures::future::pending())
+                .probe_timeout(Duration::from_millis(10)),
+        );
+        module_provider.into_module_container()
+    }
+
+    #[tokio::test]
+    async fn test_health_report() {
+        let container = container();
+
+        let liveness = container.liveness().await;
+        assert!(liveness.is_up());
+        assert_eq!(1, liveness.modules.len());
+        assert_eq!("u16", liveness.modules[0].module);
+
+        let readiness = container.readiness().await;
+        assert!(!readiness.is_up());
+        assert_eq!(3, readiness.modules.len());
+        assert_eq!(HealthStatus::Up, readiness.modules[0].status);
+        assert_eq!(HealthStatus::Down, readiness.modules[1].status);
+        assert_eq!(Some("connection refused".to_string()), readiness.modules[1].error);
+        assert_eq!(HealthStatus::Down, readiness.modules[2].status);
+        assert_eq!(Some("timed out after 10ms".to_string()), readiness.modules[2].error);
+    }
+
+    #[actix_rt::test]
+    async fn test_health_service() {
+        let container = container();
+        let mut app = test::init_service(App::new().service(container.health_service("/health"))).await;
+
+        let res = test::call_service(&mut app, test::TestRequest::get().uri("/health/live").to_request()).await;
+        assert_eq!(StatusCode::OK, res.status());
+
+        let res = test::call_service(&mut app, test::TestRequest::get().uri("/health/ready").to_request()).await;
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, res.status());
+
+        let body: Value = test::read_body_json(res).await;
+        assert_eq!("down", body["status"]);
+        assert_eq!("u32", body["modules"][1]["module"]);
+        assert_eq!("connection refused", body["modules"][1]["error"]);
+        assert_eq!("timed out after 10ms", body["modules"][2]["error"]);
+    }
+}
@@ -0,0 +1,259 @@
+//! 模块注册信息
+//!
+//! [`ModuleProvider`](crate::module::ModuleProvider) 在注册模块时会记录模块的类型、名称、
+//! 工厂方法耗时及依赖，构建容器后可通过 [`ModuleContainer::modules`] 查询，
+//! 或输出为启动报告：
+//!
+//! ```ignore
+//! let container = module_provider.into_module_container();
+//! info!("Application modules:\n{}", container.startup_report());
+//!
+//! HttpServer::new(move || {
+//!     App::new()
+//!         .configure(container.module_provider())
+//!         .service(container.inspect_service("/debug/modules"))
+//! })
+//! ```
+//!
+//! 调试端点将返回所有模块的注册信息及当前的就绪状态，其中包含应用内部结构，不应暴露在公网中。
+
+use std::fmt::Write;
+use std::time::Duration;
+
+use actix_web::{HttpResponse, Resource, web};
+use serde::{Serialize, Serializer};
+
+use crate::health::HealthStatus;
+use crate::module::{display_name, ModuleContainer};
+
+/// 模块注册方式
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModuleKind {
+    /// 启动时创建
+    Eager,
+    /// 首次使用时创建
+    Lazy,
+    /// 每个请求创建
+    Scoped,
+    /// 功能模块，提供路由
+    Feature,
+    /// 后台任务
+    Task,
+}
+
+impl ModuleKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ModuleKind::Eager => "eager",
+            ModuleKind::Lazy => "lazy",
+            ModuleKind::Scoped => "scoped",
+            ModuleKind::Feature => "feature",
+            ModuleKind::Task => "task",
+        }
+    }
+}
+
+/// 模块注册信息
+#[derive(Serialize, Debug, Clone)]
+pub struct ModuleInfo {
+    pub module: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub kind: ModuleKind,
+    /// 工厂方法耗时，直接写入的模块不存在
+    #[serde(rename = "factory_ms", serialize_with = "serialize_millis", skip_serializing_if = "Option::is_none")]
+    pub factory_duration: Option<Duration>,
+    /// 依赖的模块，仅通过 [`ModuleDefinition`](crate::module::ModuleDefinition) 注册的模块存在
+    pub dependencies: Vec<String>,
+    /// 是否已被测试替身覆盖
+    pub overridden: bool,
+    /// 就绪状态，仅由 [`ModuleContainer::inspect`] 填充，未提供就绪探针的模块不存在
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<HealthStatus>,
+}
+
+impl ModuleInfo {
+    pub(crate) fn new(module: &'static str, name: Option<&str>, kind: ModuleKind) -> Self {
+        ModuleInfo {
+            module,
+            name: name.map(ToString::to_string),
+            kind,
+            factory_duration: None,
+            dependencies: vec![],
+            overridden: false,
+            health: None,
+        }
+    }
+
+    pub(crate) fn is(&self, module: &str, name: Option<&str>) -> bool {
+        self.module == module && self.name.as_deref() == name
+    }
+
+    pub fn display_name(&self) -> String {
+        display_name(self.module, self.name.as_deref())
+    }
+}
+
+fn serialize_millis<S: Serializer>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serializer.serialize_f64(duration.as_secs_f64() * 1000.0),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// 按注册顺序写入或替换模块注册信息
+pub(crate) fn record(infos: &mut Vec<ModuleInfo>, info: ModuleInfo) {
+    match infos.iter_mut().find(|exists| exists.is(info.module, info.name.as_deref())) {
+        Some(exists) => *exists = info,
+        None => infos.push(info),
+    }
+}
+
+/// 标记模块已被覆盖，模块尚未注册时追加到末尾
+pub(crate) fn mark_overridden(infos: &mut Vec<ModuleInfo>, module: &'static str, name: Option<&str>) {
+    match infos.iter_mut().find(|exists| exists.is(module, name)) {
+        Some(exists) => exists.overridden = true,
+        None => {
+            let mut info = ModuleInfo::new(module, name, ModuleKind::Eager);
+            info.overridden = true;
+            infos.push(info);
+        }
+    }
+}
+
+impl ModuleContainer {
+    /// 查询模块注册信息，同时执行就绪探针以获取各模块的健康状态
+    pub async fn inspect(&self) -> Vec<ModuleInfo> {
+        let report = self.readiness().await;
+        let mut modules = self.modules().to_vec();
+
+        for health in report.modules {
+            if let Some(info) = modules.iter_mut().find(|info| info.is(health.module, health.name.as_deref())) {
+                info.health = Some(health.status);
+            }
+        }
+
+        modules
+    }
+
+    /// 以表格形式输出模块注册信息，用于在启动时打印
+    pub fn startup_report(&self) -> String {
+        let modules = self.modules();
+        let rows = modules.iter()
+            .map(|info| [
+                info.display_name(),
+                info.kind.as_str().to_string(),
+                info.factory_duration.map(|duration| format!("{:?}", duration)).unwrap_or_else(|| "-".to_string()),
+                if info.dependencies.is_empty() { "-".to_string() } else { info.dependencies.join(", ") },
+                if info.overridden { "yes".to_string() } else { "-".to_string() },
+            ])
+            .collect::<Vec<_>>();
+
+        let header = ["Module", "Kind", "Factory", "Dependencies", "Overridden"];
+        let mut widths = header.iter().map(|column| column.len()).collect::<Vec<_>>();
+        for row in rows.iter() {
+            for (width, column) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(column.chars().count());
+            }
+        }
+
+        let mut report = String::new();
+        let line = |report: &mut String, columns: Vec<&str>| {
+            let columns = columns.iter().zip(widths.iter())
+                .map(|(column, width)| format!("{:width$}", column, width = width))
+                .collect::<Vec<_>>();
+            let _ = writeln!(report, "| {} |", columns.join(" | "));
+        };
+
+        line(&mut report, header.to_vec());
+        line(&mut report, widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().iter().map(String::as_str).collect());
+        for row in rows.iter() {
+            line(&mut report, row.iter().map(String::as_str).collect());
+        }
+
+        let total = modules.iter().filter_map(|info| info.factory_duration).sum::<Duration>();
+        let _ = write!(report, "{} modules registered, factories took {:?} in total.", modules.len(), total);
+
+        report
+    }
+
+    /// 模块调试端点，以 JSON 格式返回 [`inspect`](ModuleContainer::inspect) 的结果
+    pub fn inspect_service(&self, path: &str) -> Resource {
+        web::resource(path)
+            .data(self.clone())
+            .route(web::get().to(inspect_handler))
+    }
+}
+
+async fn inspect_handler(container: web::Data<ModuleContainer>) -> HttpResponse {
+    HttpResponse::Ok().json(container.inspect().await)
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, test};
+
+    use crate::lifecycle::ModuleHooks;
+    use crate::module::{ModuleDefinition, ModuleProvider};
+
+    use super::*;
+
+    async fn register_u8(_: &ModuleProvider) -> std::io::Result<u8> {
+        Ok(8)
+    }
+
+    async fn register_u16(ctx: &ModuleProvider) -> std::io::Result<u16> {
+        Ok(ctx.get::<u8>().unwrap() as u16 * 2)
+    }
+
+    async fn container() -> ModuleContainer {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert("config");
+        module_provider.register_all(vec![
+            ModuleDefinition::with_hooks(register_u16, ModuleHooks::new().readiness(|_| async { Ok(()) }))
+                .depends_on::<u8>()
+                .after::<u32>(),
+            ModuleDefinition::new(register_u8),
+        ]).await.unwrap();
+        module_provider.register_lazy(|_: &ModuleProvider| async { Ok::<_, std::io::Error>(32u32) });
+        module_provider.override_named_with("replica", 64u64);
+
+        module_provider.into_module_container()
+    }
+
+    #[tokio::test]
+    async fn test_modules() {
+        let container = container().await;
+        let modules = container.modules();
+
+        let names = modules.iter().map(ModuleInfo::display_name).collect::<Vec<_>>();
+        assert_eq!(vec!["&str", "u8", "u16", "u32", "u64(replica)"], names);
+        assert!(modules[0].factory_duration.is_none());
+        assert!(modules[2].factory_duration.is_some());
+        assert_eq!(vec!["u8".to_string()], modules[2].dependencies);
+        assert_eq!(ModuleKind::Lazy, modules[3].kind);
+        assert!(modules[4].overridden);
+
+        let report = container.startup_report();
+        assert!(report.starts_with("| Module       | Kind  | Factory |"));
+        assert!(report.contains("| u64(replica) | eager | -       | -            | yes        |"));
+        assert!(report.ends_with(&format!("5 modules registered, factories took {:?} in total.",
+                                          modules[1].factory_duration.unwrap() + modules[2].factory_duration.unwrap())));
+    }
+
+    #[actix_rt::test]
+    async fn test_inspect_service() {
+        let container = container().await;
+        let mut app = test::init_service(App::new().service(container.inspect_service("/debug/modules"))).await;
+
+        let req = test::TestRequest::get().uri("/debug/modules").to_request();
+        let modules: serde_json::Value = test::read_response_json(&mut app, req).await;
+
+        assert_eq!("u16", modules[2]["module"]);
+        assert_eq!("up", modules[2]["health"]);
+        assert!(modules[2]["factory_ms"].is_number());
+        assert!(modules[0].get("health").is_none());
+        assert_eq!("replica", modules[4]["name"]);
+    }
+}
@@ -0,0 +1,211 @@
+//! 以接口（trait 对象）注册模块
+//!
+//! 模块可以以 `Arc<dyn Trait>` 的形式注册，服务依赖接口而非具体实现，便于按运行环境切换实现：
+//!
+//! ```ignore
+//! pub trait Mailer: Send + Sync {
+//!     fn send(&self, to: &str, content: &str) -> Result<(), MailError>;
+//! }
+//!
+//! // 注册
+//! module_provider.register_if(Condition::profile("prod"), bind(smtp_mailer, |mailer| Arc::new(mailer) as Arc<dyn Mailer>)).await?;
+//! module_provider.insert_interface::<dyn Mailer>(Arc::new(LogMailer));
+//!
+//! // 注入
+//! pub struct RegisterService(Arc<dyn Mailer>);
+//!
+//! impl IntoService<(Arc<dyn Mailer>, )> for RegisterService {
+//!     fn init(deps: (Arc<dyn Mailer>, )) -> Self {
+//!         RegisterService(deps.0)
+//!     }
+//! }
+//! ```
+//!
+//! 接口需要声明 `Send + Sync` 约束，`Arc<dyn Trait>` 才能作为模块注册。
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use futures::future::{FutureExt, LocalBoxFuture};
+
+use crate::error::BootstrapError;
+use crate::module::{ModuleContainer, ModuleFactoryFn, ModuleKey, ModuleProvider};
+
+/// 将具体实现的工厂方法转换为接口的工厂方法，参见 [`bind`]
+pub struct Bind<F, T, I: ?Sized> {
+    factory: F,
+    convert: fn(T) -> Arc<I>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<F: Clone, T, I: ?Sized> Clone for Bind<F, T, I> {
+    fn clone(&self) -> Self {
+        Bind {
+            factory: self.factory.clone(),
+            convert: self.convert,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// 将返回具体实现 `T` 的工厂方法绑定为接口 `I` 的工厂方法
+///
+/// 返回值可以用于所有接收工厂方法的场合，例如 [`ModuleProvider::register`] 及 [`ModuleDefinition::new`]：
+///
+/// ```ignore
+/// ModuleDefinition::new(bind(smtp_mailer, |mailer| Arc::new(mailer) as Arc<dyn Mailer>))
+/// ```
+///
+/// [`ModuleDefinition::new`]: crate::module::ModuleDefinition::new
+pub fn bind<F, T, I: ?Sized>(factory: F, convert: fn(T) -> Arc<I>) -> Bind<F, T, I> {
+    Bind { factory, convert, _marker: PhantomData }
+}
+
+impl<'a, F, T, I, E> ModuleFactoryFn<'a, Arc<I>, E> for Bind<F, T, I>
+    where F: ModuleFactoryFn<'a, T, E>,
+          T: Send + Sync + Clone + 'static,
+          I: ?Sized + Send + Sync + 'static,
+          E: std::error::Error + Send + Sync + 'static
+{
+    type Res = LocalBoxFuture<'a, Result<Arc<I>, E>>;
+
+    fn call(self, s: &'a ModuleProvider) -> Self::Res {
+        let convert = self.convert;
+        self.factory.call(s).map(move |result| result.map(convert)).boxed_local()
+    }
+}
+
+impl ModuleProvider {
+    /// 写入接口的实现
+    pub fn insert_interface<I>(&mut self, implementation: Arc<I>)
+        where I: ?Sized + Send + Sync + 'static
+    {
+        self.insert(implementation)
+    }
+
+    /// 写入接口的具名实现
+    pub fn insert_named_interface<I>(&mut self, name: &str, implementation: Arc<I>)
+        where I: ?Sized + Send + Sync + 'static
+    {
+        self.insert_named(name, implementation)
+    }
+
+    /// 通过返回具体实现的工厂方法注册接口
+    pub async fn register_interface<I, T, F, E>(&mut self, factory: F, convert: fn(T) -> Arc<I>) -> Result<(), BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E>,
+                T: Send + Sync + Clone + 'static,
+                I: ?Sized + Send + Sync + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        self.register(bind(factory, convert)).await
+    }
+
+    /// 获取接口的实现
+    pub fn get_interface<I>(&self) -> Option<Arc<I>>
+        where I: ?Sized + Send + Sync + 'static
+    {
+        self.get::<Arc<I>>()
+    }
+}
+
+impl ModuleContainer {
+    /// 获取接口的实现
+    pub fn get_interface<I>(&self) -> Option<Arc<I>>
+        where I: ?Sized + Send + Sync + 'static
+    {
+        self.module::<Arc<I>>(&ModuleKey::of::<Arc<I>>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, test, web};
+
+    use crate::condition::Condition;
+    use crate::config::Profile;
+    use crate::error::Error;
+    use crate::module::ModuleDefinition;
+    use crate::qualifier;
+    use crate::service::{IntoService, Named, Service};
+
+    use super::*;
+
+    trait Mailer: Send + Sync {
+        fn send(&self, to: &str) -> String;
+    }
+
+    #[derive(Clone)]
+    struct SmtpMailer(&'static str);
+
+    impl Mailer for SmtpMailer {
+        fn send(&self, to: &str) -> String {
+            format!("sent to {} via {}", to, self.0)
+        }
+    }
+
+    struct LogMailer;
+
+    impl Mailer for LogMailer {
+        fn send(&self, to: &str) -> String {
+            format!("logged mail to {}", to)
+        }
+    }
+
+    async fn smtp_mailer(_: &ModuleProvider) -> std::io::Result<SmtpMailer> {
+        Ok(SmtpMailer("smtp.example.com"))
+    }
+
+    qualifier!(Backup = "backup");
+
+    struct RegisterService(Arc<dyn Mailer>, Named<Arc<dyn Mailer>, Backup>);
+
+    impl IntoService<(Arc<dyn Mailer>, Named<Arc<dyn Mailer>, Backup>)> for RegisterService {
+        fn init(deps: (Arc<dyn Mailer>, Named<Arc<dyn Mailer>, Backup>)) -> Self {
+            RegisterService(deps.0, deps.1)
+        }
+    }
+
+    async fn bootstrap(profile: &str) -> ModuleContainer {
+        let mut module_provider = ModuleProvider::initialize(Profile::new(profile));
+        module_provider.register_all(vec![
+            ModuleDefinition::new(bind(smtp_mailer, |mailer| Arc::new(mailer) as Arc<dyn Mailer>))
+                .when(Condition::profile("prod")),
+        ]).await.unwrap();
+
+        if !module_provider.contains::<Arc<dyn Mailer>>() {
+            module_provider.insert_interface::<dyn Mailer>(Arc::new(LogMailer));
+        }
+        module_provider.insert_named_interface::<dyn Mailer>("backup", Arc::new(LogMailer));
+
+        module_provider.into_module_container()
+    }
+
+    async fn send(container: &ModuleContainer) -> String {
+        let mut app = test::init_service(
+            App::new()
+                .configure(container.module_provider())
+                .route("/register", web::post().to(|srv: Service| async move {
+                    let register_service = srv.get::<_, RegisterService>()?;
+                    Ok::<_, Error>(format!("{}; {}", register_service.0.send("alice"), register_service.1.send("bob")))
+                }))
+        ).await;
+
+        let res = test::call_service(&mut app, test::TestRequest::post().uri("/register").to_request()).await;
+        String::from_utf8(test::read_body(res).await.to_vec()).unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn test_interface() {
+        let container = bootstrap("prod").await;
+        assert_eq!("sent to alice via smtp.example.com", container.get_interface::<dyn Mailer>().unwrap().send("alice"));
+        assert_eq!("sent to alice via smtp.example.com; logged mail to bob", send(&container).await);
+
+        let container = bootstrap("dev").await;
+        assert_eq!("logged mail to alice; logged mail to bob", send(&container).await);
+
+        let mut module_provider = ModuleProvider::new();
+        module_provider.register_interface(smtp_mailer, |mailer| Arc::new(mailer) as Arc<dyn Mailer>).await.unwrap();
+        assert!(module_provider.get_interface::<dyn Mailer>().is_some());
+    }
+}
@@ -0,0 +1,213 @@
+//! 延迟初始化模块
+//!
+//! 通过 [`ModuleProvider::register_lazy`] 注册的模块不会在启动时执行工厂方法，而是在首次被解析时
+//! 才进行初始化，并缓存初始化结果。适用于启动时可能不可用、且并非所有服务都会用到的依赖。
+//! 初始化失败不会被缓存，下次解析时将重新执行工厂方法。
+//!
+//! 延迟模块可以通过 [`Lazy`] 注入到服务中：
+//!
+//! ```ignore
+//! pub struct CacheService (Lazy<MultiplexedConnection>);
+//!
+//! impl CacheService {
+//!     pub async fn get(&self, key: &str) -> Result<String, Error> {
+//!         let mut conn = self.0.get().await?;
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! 工厂方法执行时接收的模块注册器为当时模块容器的快照，其中不包含尚未初始化的延迟模块。
+
+use std::any::{Any, type_name};
+use std::sync::Arc;
+
+use actix_web::web::ServiceConfig;
+use futures::future::LocalBoxFuture;
+use futures::lock::Mutex;
+
+use crate::error::Error;
+use crate::module::{ModuleContainer, ModuleFactoryFn, ModuleKey, ModuleProvider, ModuleRegister};
+use crate::service::{Dependency, ResolveContext};
+
+type LazyFactory<T> = Box<dyn Fn(ModuleProvider) -> LocalBoxFuture<'static, Result<T, Box<dyn std::error::Error + Send + Sync>>> + Send + Sync>;
+
+/// 延迟初始化的模块
+pub struct LazyModule<T> {
+    factory: LazyFactory<T>,
+    module: Mutex<Option<T>>,
+}
+
+impl<T> LazyModule<T>
+    where T: Send + Sync + Clone + 'static
+{
+    pub(crate) fn new<F, E>(factory: F) -> Self
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E> + Clone + Send + Sync + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        LazyModule {
+            factory: Box::new(move |provider| {
+                let factory = factory.clone();
+                Box::pin(async move {
+                    let module = factory.call(&provider).await?;
+                    Ok(module)
+                })
+            }),
+            module: Mutex::new(None),
+        }
+    }
+
+    /// 创建已完成初始化的延迟模块，用于覆盖延迟模块
+    pub(crate) fn initialized(module: T) -> Self {
+        let factory_module = module.clone();
+        LazyModule {
+            factory: Box::new(move |_| {
+                let module = factory_module.clone();
+                Box::pin(async move { Ok(module) })
+            }),
+            module: Mutex::new(Some(module)),
+        }
+    }
+
+    /// 获取模块，尚未初始化时执行工厂方法
+    ///
+    /// 并发获取时仅有一个调用方执行初始化，其余调用方等待其结果。
+    pub(crate) async fn get(&self, container: &ModuleContainer) -> Result<T, Error> {
+        let mut module = self.module.lock().await;
+        if let Some(module) = module.as_ref() {
+            return Ok(module.clone());
+        }
+
+        info!("Initialize lazy module [{}].", type_name::<T>());
+        let initialized = (self.factory)(ModuleProvider::snapshot(container))
+            .await
+            .map_err(|source| {
+                error!("Initialize lazy module [{}] error: {}", type_name::<T>(), source);
+                Error::DependencyInitFailed { module: type_name::<T>(), source }
+            })?;
+
+        *module = Some(initialized.clone());
+        Ok(initialized)
+    }
+
+    /// 是否已完成初始化
+    pub fn is_initialized(&self) -> bool {
+        self.module.try_lock().map(|module| module.is_some()).unwrap_or(false)
+    }
+}
+
+/// 延迟模块注册器
+///
+/// 延迟模块不会注册为 actix web 的应用数据，需通过 [`Lazy`] 注入。
+pub(crate) struct LazyModuleRegister<T>(pub Arc<LazyModule<T>>);
+
+impl<T> ModuleRegister for LazyModuleRegister<T>
+    where T: Send + Sync + Clone + 'static
+{
+    fn register(&self, _service: &mut ServiceConfig) {}
+
+    fn get_module(&self) -> Box<dyn Any> {
+        Box::new(self.0.clone())
+    }
+
+    fn module_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+}
+
+/// 延迟模块依赖
+///
+/// 解析依赖时并不会初始化模块，直到首次调用 [`Lazy::get`]。
+pub struct Lazy<T> {
+    module: Arc<LazyModule<T>>,
+    container: ModuleContainer,
+}
+
+impl<T> Lazy<T>
+    where T: Send + Sync + Clone + 'static
+{
+    /// 获取模块，首次获取时执行初始化
+    pub async fn get(&self) -> Result<T, Error> {
+        self.module.get(&self.container).await
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.module.is_initialized()
+    }
+}
+
+impl<T> Dependency for Lazy<T>
+    where T: Send + Sync + Clone + 'static
+{
+    fn resolve(ctx: ResolveContext<'_>) -> Result<Self, Error> {
+        ctx.container()
+            .and_then(|container| container.lazy::<T>())
+            .ok_or(Error::DependencyNotFound(type_name::<T>()))
+    }
+}
+
+impl ModuleContainer {
+    /// 获取延迟模块的句柄
+    pub fn lazy<T>(&self) -> Option<Lazy<T>>
+        where T: Send + Sync + Clone + 'static
+    {
+        self.get_shared::<LazyModule<T>>(&ModuleKey::of::<LazyModule<T>>())
+            .map(|module| Lazy { module, container: self.clone() })
+    }
+
+    /// 获取延迟模块，首次获取时执行初始化
+    pub async fn get_lazy<T>(&self) -> Result<T, Error>
+        where T: Send + Sync + Clone + 'static
+    {
+        self.lazy::<T>()
+            .ok_or(Error::DependencyNotFound(type_name::<T>()))?
+            .get()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    async fn register_connection(ctx: &ModuleProvider) -> std::io::Result<String> {
+        if CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "connection refused"));
+        }
+
+        Ok(format!("connected to {}", ctx.get_ref::<&'static str>().unwrap()))
+    }
+
+    #[tokio::test]
+    async fn test_lazy_module() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.register_lazy(register_connection);
+        module_provider.insert("127.0.0.1");
+
+        assert!(!module_provider.contains::<String>());
+        assert_eq!(0, CALLS.load(Ordering::SeqCst));
+
+        let container = module_provider.into_module_container();
+        let lazy = container.lazy::<String>().unwrap();
+        assert!(!lazy.is_initialized());
+
+        let err = lazy.get().await.unwrap_err();
+        assert_eq!(
+            "Cannot initialize dependency: alloc::string::String, cause: connection refused",
+            err.to_string()
+        );
+        assert!(!lazy.is_initialized());
+
+        assert_eq!("connected to 127.0.0.1", container.get_lazy::<String>().await.unwrap());
+        assert_eq!("connected to 127.0.0.1", lazy.get().await.unwrap());
+        assert!(lazy.is_initialized());
+        assert_eq!(2, CALLS.load(Ordering::SeqCst));
+
+        assert!(container.lazy::<u8>().is_none());
+    }
+}
@@ -5,17 +5,51 @@ pub mod module;
 pub mod service;
 pub mod error;
 pub mod config;
+pub mod placeholder;
+pub mod lifecycle;
+pub mod health;
+pub mod lazy;
+pub mod scope;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod inspect;
+pub mod feature;
+pub mod retry;
+pub mod reload;
+pub mod condition;
+pub mod interface;
+pub mod task;
+pub mod event;
 
 
 pub mod preludes {
-    pub use crate::module::{ModuleFactoryFn, ModuleProvider, ModuleContainer};
+    pub use crate::module::{ModuleFactoryFn, ModuleProvider, ModuleContainer, ModuleDefinition};
+    pub use crate::lifecycle::ModuleHooks;
+    pub use crate::feature::FeatureModule;
+    pub use crate::condition::Condition;
     pub use crate::config;
     pub use crate::service;
-    pub use crate::error::Error;
+    pub use crate::error::{BootstrapError, Error};
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// 创建测试独占的临时目录，避免同时运行的测试互相覆盖文件
+    pub(crate) fn temp_dir(name: &str) -> PathBuf {
+        static SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let dir = std::env::temp_dir().join(format!(
+            "inspirer-{}-{}-{}-{}", name, std::process::id(), nanos, SEQUENCE.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);
@@ -0,0 +1,201 @@
+//! 模块生命周期
+//!
+//! 通过 [`ModuleHooks`] 为注册的模块附加启动、就绪与关闭钩子，这些钩子由
+//! [`ModuleContainer`](crate::module::ModuleContainer) 按模块注册顺序调用（关闭时逆序调用），
+//! 便于在应用停止时释放连接池等资源。
+//!
+//! ```
+//! use inspirer_actix_ext_core::lifecycle::ModuleHooks;
+//! use inspirer_actix_ext_core::module::{ModuleDefinition, ModuleProvider};
+//!
+//! async fn connection_factory(_: &ModuleProvider) -> std::io::Result<String> {
+//!     Ok("connection".into())
+//! }
+//!
+//! let definition = ModuleDefinition::with_hooks(
+//!     connection_factory,
+//!     ModuleHooks::new().on_shutdown(|conn: String| async move {
+//!         println!("close {}", conn);
+//!         Ok(())
+//!     }),
+//! );
+//! ```
+
+use std::any::{Any, type_name, TypeId};
+use std::future::Future;
+use std::time::Duration;
+
+use actix_web::web::ServiceConfig;
+use futures::future::{FutureExt, LocalBoxFuture, ok};
+
+use crate::module::ModuleRegister;
+
+type LifecycleHook<T> = Box<dyn Fn(T) -> LocalBoxFuture<'static, anyhow::Result<()>> + Send + Sync>;
+
+/// 模块生命周期钩子
+pub struct ModuleHooks<T> {
+    on_start: Option<LifecycleHook<T>>,
+    on_ready: Option<LifecycleHook<T>>,
+    on_shutdown: Option<LifecycleHook<T>>,
+    liveness: Option<LifecycleHook<T>>,
+    readiness: Option<LifecycleHook<T>>,
+    probe_timeout: Option<Duration>,
+}
+
+impl<T> Default for ModuleHooks<T> {
+    fn default() -> Self {
+        ModuleHooks {
+            on_start: None,
+            on_ready: None,
+            on_shutdown: None,
+            liveness: None,
+            readiness: None,
+            probe_timeout: None,
+        }
+    }
+}
+
+impl<T> ModuleHooks<T>
+    where T: Send + Sync + Clone + 'static
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 启动钩子，在应用服务启动前调用
+    pub fn on_start<F, R>(mut self, hook: F) -> Self
+        where F: Fn(T) -> R + Send + Sync + 'static,
+              R: Future<Output=anyhow::Result<()>> + 'static
+    {
+        self.on_start = Some(Box::new(move |module| hook(module).boxed_local()));
+        self
+    }
+
+    /// 就绪钩子，在应用服务开始接收请求后调用
+    pub fn on_ready<F, R>(mut self, hook: F) -> Self
+        where F: Fn(T) -> R + Send + Sync + 'static,
+              R: Future<Output=anyhow::Result<()>> + 'static
+    {
+        self.on_ready = Some(Box::new(move |module| hook(module).boxed_local()));
+        self
+    }
+
+    /// 关闭钩子，在应用服务停止（已处理完进行中的请求）后调用
+    pub fn on_shutdown<F, R>(mut self, hook: F) -> Self
+        where F: Fn(T) -> R + Send + Sync + 'static,
+              R: Future<Output=anyhow::Result<()>> + 'static
+    {
+        self.on_shutdown = Some(Box::new(move |module| hook(module).boxed_local()));
+        self
+    }
+
+    /// 存活探针，用于判断应用是否需要被重启
+    pub fn liveness<F, R>(mut self, probe: F) -> Self
+        where F: Fn(T) -> R + Send + Sync + 'static,
+              R: Future<Output=anyhow::Result<()>> + 'static
+    {
+        self.liveness = Some(Box::new(move |module| probe(module).boxed_local()));
+        self
+    }
+
+    /// 就绪探针，用于判断应用是否可以接收流量，例如数据库连接是否可用
+    pub fn readiness<F, R>(mut self, probe: F) -> Self
+        where F: Fn(T) -> R + Send + Sync + 'static,
+              R: Future<Output=anyhow::Result<()>> + 'static
+    {
+        self.readiness = Some(Box::new(move |module| probe(module).boxed_local()));
+        self
+    }
+
+    /// 探针超时时间，超时的探针视为失败，默认为 [`DEFAULT_PROBE_TIMEOUT`](crate::health::DEFAULT_PROBE_TIMEOUT)
+    pub fn probe_timeout(mut self, timeout: Duration) -> Self {
+        self.probe_timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.on_start.is_none()
+            && self.on_ready.is_none()
+            && self.on_shutdown.is_none()
+            && self.liveness.is_none()
+            && self.readiness.is_none()
+    }
+}
+
+/// 带有生命周期钩子的模块注册器
+///
+/// 具名模块不会注册为 actix web 的应用数据，而是通过模块容器解析。
+pub struct LifecycleModuleRegister<T> {
+    name: Option<String>,
+    module: T,
+    hooks: ModuleHooks<T>,
+}
+
+impl<T> LifecycleModuleRegister<T>
+    where T: Send + Sync + Clone + 'static
+{
+    pub fn boxed(module: T, hooks: ModuleHooks<T>) -> Box<dyn ModuleRegister> {
+        Box::new(LifecycleModuleRegister { name: None, module, hooks })
+    }
+
+    pub fn named(name: &str, module: T, hooks: ModuleHooks<T>) -> Box<dyn ModuleRegister> {
+        Box::new(LifecycleModuleRegister { name: Some(name.to_string()), module, hooks })
+    }
+
+    fn call(&self, hook: &Option<LifecycleHook<T>>) -> LocalBoxFuture<'static, anyhow::Result<()>> {
+        match hook {
+            Some(hook) => hook(self.module.clone()),
+            None => ok(()).boxed_local(),
+        }
+    }
+}
+
+impl<T> ModuleRegister for LifecycleModuleRegister<T>
+    where T: Send + Sync + Clone + 'static
+{
+    fn register(&self, service: &mut ServiceConfig) {
+        if self.name.is_none() {
+            service.data(self.module.clone());
+        }
+    }
+
+    fn get_module(&self) -> Box<dyn Any> {
+        Box::new(self.module.clone())
+    }
+
+    fn module_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+
+    fn module_type(&self) -> Option<TypeId> {
+        Some(TypeId::of::<T>())
+    }
+
+    fn module_qualifier(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn probe_timeout(&self) -> Option<Duration> {
+        self.hooks.probe_timeout
+    }
+
+    fn on_start(&self) -> LocalBoxFuture<'static, anyhow::Result<()>> {
+        self.call(&self.hooks.on_start)
+    }
+
+    fn on_ready(&self) -> LocalBoxFuture<'static, anyhow::Result<()>> {
+        self.call(&self.hooks.on_ready)
+    }
+
+    fn on_shutdown(&self) -> LocalBoxFuture<'static, anyhow::Result<()>> {
+        self.call(&self.hooks.on_shutdown)
+    }
+
+    fn liveness_probe(&self) -> Option<LocalBoxFuture<'static, anyhow::Result<()>>> {
+        self.hooks.liveness.as_ref().map(|probe| probe(self.module.clone()))
+    }
+
+    fn readiness_probe(&self) -> Option<LocalBoxFuture<'static, anyhow::Result<()>>> {
+        self.hooks.readiness.as_ref().map(|probe| probe(self.module.clone()))
+    }
+}
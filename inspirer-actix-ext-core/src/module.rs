@@ -29,13 +29,62 @@
 //! let mut module_provider = ModuleProvider::new();
 //! module_provider.register(database_conn_factory);
 //! ```
+//!
+//! 当模块之间存在依赖关系时，可以通过 [`ModuleDefinition`] 声明工厂方法所依赖的模块，
+//! 再交由 [`ModuleProvider::register_all`] 按依赖顺序统一注册，而不必手动维护注册顺序。
+//!
+//! ```
+//! use inspirer_actix_ext_core::error::BootstrapError;
+//! use inspirer_actix_ext_core::module::{ModuleDefinition, ModuleProvider};
+//! use std::io::Result;
+//!
+//! async fn config_factory(_: &ModuleProvider) -> Result<String> {
+//!     Ok("database config".into())
+//! }
+//!
+//! async fn database_conn_factory(ctx: &ModuleProvider) -> Result<&'static str> {
+//!     let _config = ctx.get_ref::<String>().unwrap();
+//!     Ok("database conn")
+//! }
+//!
+//! async fn bootstrap() -> std::result::Result<(), BootstrapError> {
+//!     let mut module_provider = ModuleProvider::new();
+//!     module_provider.register_all(vec![
+//!         ModuleDefinition::new(database_conn_factory).depends_on::<String>(),
+//!         ModuleDefinition::new(config_factory),
+//!     ]).await
+//! }
+//! ```
+//!
+//! 若各模块的依赖均已完整声明，还可以使用 [`ModuleProvider::register_all_concurrent`]，
+//! 相互独立的工厂方法（例如分别建立数据库与 Redis 连接）将并发执行，以缩短应用的启动时间。
 
 use std::any::{Any, type_name, TypeId};
+use std::convert::Infallible;
 use std::future::Future;
+use std::ops::Deref;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use actix_web::HttpRequest;
+use actix_web::dev::Server;
 use actix_web::web::ServiceConfig;
-use ahash::AHashMap;
+use ahash::{AHashMap, AHashSet};
+use anyhow::Context;
+use config::Config;
+use futures::future::{Either, FutureExt, join_all, LocalBoxFuture, ok, select};
+use futures_timer::Delay;
+
+use crate::condition::Condition;
+use crate::error::BootstrapError;
+use crate::feature::{FeatureConfig, FeatureModule, FeatureModuleRegister};
+use crate::inspect::{self, ModuleInfo, ModuleKind};
+use crate::lazy::{LazyModule, LazyModuleRegister};
+use crate::lifecycle::{LifecycleModuleRegister, ModuleHooks};
+use crate::reload::Reloadable;
+use crate::retry::RetryPolicy;
+use crate::scope::{AsyncScopedFactory, AsyncScopedModuleRegister, Scoped, ScopedFactory, ScopedModuleRegister};
+use crate::task::{BackgroundTask, TaskModuleRegister};
 
 /// 应用模块注册器 trait
 pub trait ModuleRegister: Sync + Send + Any {
@@ -47,6 +96,63 @@ pub trait ModuleRegister: Sync + Send + Any {
 
     /// 获取模块
     fn get_module(&self) -> Box<dyn Any>;
+
+    /// 模块名称，用于日志及错误信息
+    fn module_name(&self) -> &'static str {
+        type_name::<Self>()
+    }
+
+    /// 模块类型，用于替换（覆盖）已注册的模块
+    ///
+    /// 返回 `None` 表示该注册器不支持被覆盖
+    fn module_type(&self) -> Option<TypeId> {
+        None
+    }
+
+    /// 模块限定名，仅具名注册的模块存在
+    fn module_qualifier(&self) -> Option<&str> {
+        None
+    }
+
+    /// 健康检查探针的超时时间，未指定时使用 [`DEFAULT_PROBE_TIMEOUT`](crate::health::DEFAULT_PROBE_TIMEOUT)
+    fn probe_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// 模块启动钩子
+    ///
+    /// 在应用服务启动前，按模块注册顺序调用
+    fn on_start(&self) -> LocalBoxFuture<'static, anyhow::Result<()>> {
+        ok(()).boxed_local()
+    }
+
+    /// 模块就绪钩子
+    ///
+    /// 在应用服务开始接收请求后，按模块注册顺序调用
+    fn on_ready(&self) -> LocalBoxFuture<'static, anyhow::Result<()>> {
+        ok(()).boxed_local()
+    }
+
+    /// 模块关闭钩子
+    ///
+    /// 在应用服务停止后，按模块注册的逆序调用
+    fn on_shutdown(&self) -> LocalBoxFuture<'static, anyhow::Result<()>> {
+        ok(()).boxed_local()
+    }
+
+    /// 存活探针
+    ///
+    /// 返回 `None` 表示该模块不参与存活检查
+    fn liveness_probe(&self) -> Option<LocalBoxFuture<'static, anyhow::Result<()>>> {
+        None
+    }
+
+    /// 就绪探针
+    ///
+    /// 返回 `None` 表示该模块不参与就绪检查
+    fn readiness_probe(&self) -> Option<LocalBoxFuture<'static, anyhow::Result<()>>> {
+        None
+    }
 }
 
 /// 标准应用模块注册器
@@ -73,42 +179,324 @@ impl<T> ModuleRegister for StandardModuleRegister<T>
     fn get_module(&self) -> Box<dyn Any> {
         Box::new(self.0.clone())
     }
+
+    fn module_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+
+    fn module_type(&self) -> Option<TypeId> {
+        Some(TypeId::of::<T>())
+    }
+}
+
+/// 模块索引键
+///
+/// 由模块类型与可选的模块名称组成，未命名的模块名称为 `None`。
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ModuleKey {
+    type_id: TypeId,
+    name: Option<String>,
+}
+
+impl ModuleKey {
+    pub(crate) fn of<T: 'static>() -> Self {
+        ModuleKey { type_id: TypeId::of::<T>(), name: None }
+    }
+
+    pub(crate) fn named<T: 'static>(name: &str) -> Self {
+        ModuleKey { type_id: TypeId::of::<T>(), name: Some(name.to_string()) }
+    }
+
+    pub(crate) fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn matches(&self, register: &dyn ModuleRegister) -> bool {
+        register.module_type() == Some(self.type_id) && register.module_qualifier() == self.name.as_deref()
+    }
+}
+
+/// 替换与索引键对应的模块注册器，保持其原有的注册顺序；不存在时追加到末尾
+fn replace_register<R>(registers: &mut Vec<R>, key: &ModuleKey, register: R)
+    where R: Deref<Target=dyn ModuleRegister>
+{
+    match registers.iter().position(|exists| key.matches(exists.deref())) {
+        Some(position) => registers[position] = register,
+        None => registers.push(register),
+    }
+}
+
+/// 模块展示名称，具名模块形如 `sqlx::MySqlPool(replica)`
+pub(crate) fn display_name(type_name: &str, name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("{}({})", type_name, name),
+        None => type_name.to_string(),
+    }
 }
 
+type ModuleMap = AHashMap<ModuleKey, Arc<dyn Any + Send + Sync>>;
+
 /// Actix Web 应用模块容器
 ///
 /// 应用模块管理器是用于传递应用模块的一个容器。
 #[derive(Clone)]
-pub struct ModuleContainer(Arc<Vec<Box<dyn ModuleRegister>>>);
+pub struct ModuleContainer {
+    registers: Arc<Vec<Arc<dyn ModuleRegister>>>,
+    modules: Arc<ModuleMap>,
+    infos: Arc<Vec<ModuleInfo>>,
+}
 
 impl ModuleContainer {
-    pub fn new(inner: Vec<Box<dyn ModuleRegister>>) -> Self {
-        ModuleContainer(Arc::new(inner))
+    /// 由已注册的模块创建容器，需通过 [`ModuleProvider::into_module_container`] 获取
+    pub(crate) fn new(registers: Vec<Box<dyn ModuleRegister>>, modules: ModuleMap, infos: Vec<ModuleInfo>) -> Self {
+        ModuleContainer {
+            registers: Arc::new(registers.into_iter().map(Arc::from).collect()),
+            modules: Arc::new(modules),
+            infos: Arc::new(infos),
+        }
+    }
+
+    pub(crate) fn registers(&self) -> &[Arc<dyn ModuleRegister>] {
+        self.registers.as_slice()
+    }
+
+    /// 模块注册信息，按注册顺序排列，详见 [`inspect`](crate::inspect) 模块
+    pub fn modules(&self) -> &[ModuleInfo] {
+        self.infos.as_slice()
+    }
+
+    /// 创建覆盖了指定模块的新容器
+    ///
+    /// 主要用于测试，在复用应用启动流程所构建的容器的同时，以测试替身替换其中的模块。
+    /// 被覆盖的模块在注册顺序中的位置保持不变，但其生命周期钩子及健康探针不再生效；
+    /// 原容器不受影响。
+    pub fn with_override<T>(&self, obj: T) -> ModuleContainer
+        where T: Send + Sync + Clone + 'static
+    {
+        self.replace(ModuleKey::of::<T>(), obj.clone(), StandardModuleRegister::boxed(obj).into())
+    }
+
+    /// 创建覆盖了指定具名模块的新容器
+    pub fn with_named_override<T>(&self, name: &str, obj: T) -> ModuleContainer
+        where T: Send + Sync + Clone + 'static
+    {
+        let register = LifecycleModuleRegister::named(name, obj.clone(), ModuleHooks::default());
+        self.replace(ModuleKey::named::<T>(name), obj, register.into())
+    }
+
+    fn replace<T>(&self, key: ModuleKey, obj: T, register: Arc<dyn ModuleRegister>) -> ModuleContainer
+        where T: Send + Sync + Clone + 'static
+    {
+        info!("Override module [{}]", display_name(type_name::<T>(), key.name.as_deref()));
+
+        let mut registers = self.registers.as_ref().clone();
+        replace_register(&mut registers, &key, register);
+
+        let mut modules = self.modules.as_ref().clone();
+        if key.name.is_none() && modules.contains_key(&ModuleKey::of::<LazyModule<T>>()) {
+            modules.insert(ModuleKey::of::<LazyModule<T>>(), Arc::new(LazyModule::initialized(obj.clone())));
+        }
+        if key.name.is_none() && modules.contains_key(&ModuleKey::of::<Reloadable<T>>()) {
+            modules.insert(ModuleKey::of::<Reloadable<T>>(), Arc::new(Module(Reloadable::new(obj.clone()))));
+            modules.remove(&ModuleKey::named::<Snapshot>(type_name::<T>()));
+        }
+        let mut infos = self.infos.as_ref().clone();
+        inspect::mark_overridden(&mut infos, type_name::<T>(), key.name.as_deref());
+
+        modules.insert(key, Arc::new(Module(obj)));
+
+        ModuleContainer {
+            registers: Arc::new(registers),
+            modules: Arc::new(modules),
+            infos: Arc::new(infos),
+        }
+    }
+
+    pub(crate) fn get_shared<M>(&self, key: &ModuleKey) -> Option<Arc<M>>
+        where M: Send + Sync + 'static
+    {
+        self.modules
+            .get(key)
+            .cloned()
+            .and_then(|shared| shared.downcast::<M>().ok())
+    }
+
+    pub(crate) fn module<T>(&self, key: &ModuleKey) -> Option<T>
+        where T: Clone + 'static
+    {
+        if key.name.is_none() {
+            let reloadable = self.modules
+                .get(&ModuleKey::of::<Reloadable<T>>())
+                .and_then(|boxed| boxed.downcast_ref::<Module<Reloadable<T>>>());
+            if let Some(reloadable) = reloadable {
+                return Some(reloadable.0.get().as_ref().clone());
+            }
+        }
+
+        self.modules
+            .get(key)
+            .and_then(|boxed| boxed.downcast_ref::<Module<T>>())
+            .map(|obj| obj.0.clone())
+    }
+
+    /// 获取模块
+    ///
+    /// 可在请求处理之外（命令行、后台任务、测试等）直接获取模块，延迟模块需通过 [`ModuleContainer::get_lazy`] 获取。
+    pub fn get<T>(&self) -> Option<T>
+        where T: Send + Sync + Clone + 'static
+    {
+        self.module(&ModuleKey::of::<T>())
+    }
+
+    /// 获取具名模块
+    pub fn get_named<T>(&self, name: &str) -> Option<T>
+        where T: Send + Sync + Clone + 'static
+    {
+        self.module(&ModuleKey::named::<T>(name))
     }
 
     /// 获取模块提供者
     ///
     /// 这个方法可作为 actix web 中 App 的 `configure` 方法的参数提供。
+    /// 除各模块外，容器本身也将被注册到应用数据中，用于解析具名模块等依赖。
     pub fn module_provider(&self) -> Box<dyn FnOnce(&mut ServiceConfig)> {
-        let registers = self.0.clone();
+        let container = self.clone();
         Box::new(move |srv: &mut ServiceConfig| {
-            info!("Configure application service, [{}] modules provide.", registers.len());
+            info!("Configure application service, [{}] modules provide.", container.registers.len());
 
-            for module_register in registers.iter() {
+            for module_register in container.registers.iter() {
                 module_register.register(srv);
             }
+
+            srv.data(container);
         })
     }
+
+    /// 按注册顺序调用各模块的启动钩子
+    ///
+    /// 任一钩子失败即中止，按逆序调用已启动模块的关闭钩子后返回错误。
+    pub async fn start(&self) -> anyhow::Result<()> {
+        for (index, module_register) in self.registers.iter().enumerate() {
+            let result = module_register.on_start()
+                .await
+                .with_context(|| format!("Module [{}] failed to start", module_register.module_name()));
+
+            if result.is_err() {
+                shutdown_registers(&self.registers[..index]).await;
+                return result;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 按注册顺序调用各模块的就绪钩子
+    pub async fn ready(&self) -> anyhow::Result<()> {
+        for module_register in self.registers.iter() {
+            module_register.on_ready()
+                .await
+                .with_context(|| format!("Module [{}] failed to get ready", module_register.module_name()))?;
+        }
+
+        Ok(())
+    }
+
+    /// 按注册的逆序调用各模块的关闭钩子
+    ///
+    /// 关闭过程中单个模块的失败仅记录日志，不影响其他模块的关闭。
+    pub async fn shutdown(&self) {
+        shutdown_registers(&self.registers).await;
+    }
+
+    /// 运行 actix web 服务并管理模块生命周期
+    ///
+    /// 服务启动后调用就绪钩子并启动后台任务，待服务因停止信号（SIGINT、SIGTERM 等）完成平滑关闭后，
+    /// 再依次调用关闭钩子。启动钩子需要在构建 `HttpServer` 前通过 [`ModuleContainer::start`] 调用。
+    ///
+    /// ```ignore
+    /// let container = module_provider.into_module_container();
+    /// container.start().await?;
+    ///
+    /// let server = {
+    ///     let container = container.clone();
+    ///     HttpServer::new(move || App::new().configure(container.module_provider()))
+    ///         .bind("127.0.0.1:8080")?
+    ///         .run()
+    /// };
+    ///
+    /// container.serve(server).await?;
+    /// ```
+    pub async fn serve(&self, server: Server) -> anyhow::Result<()> {
+        if let Err(err) = self.ready().await {
+            server.stop(true).await;
+            self.shutdown().await;
+            return Err(err);
+        }
+
+        self.start_tasks();
+
+        let result = server.await;
+        info!("Application server stopped, shutdown modules.");
+        self.shutdown().await;
+
+        Ok(result?)
+    }
+}
+
+async fn shutdown_registers(registers: &[Arc<dyn ModuleRegister>]) {
+    for module_register in registers.iter().rev() {
+        debug!("Shutdown module [{}].", module_register.module_name());
+        if let Err(err) = module_register.on_shutdown().await {
+            error!("Module [{}] failed to shutdown: {:?}", module_register.module_name(), err);
+        }
+    }
 }
 
 #[derive(Clone)]
-struct Module<T: Send + Sync + Clone>(pub T);
+struct Module<T: Clone>(pub T);
+
+/// 可热替换模块的快照刷新方法，返回当前实例，用于在启动后执行的工厂方法中读取最新实例
+#[derive(Clone)]
+struct Snapshot(Arc<dyn Fn() -> (ModuleKey, Arc<dyn Any + Send + Sync>) + Send + Sync>);
 
-pub struct ModuleProvider(AHashMap<TypeId, Box<dyn Any>>, Vec<Box<dyn ModuleRegister>>);
+pub struct ModuleProvider {
+    modules: ModuleMap,
+    registers: Vec<Box<dyn ModuleRegister>>,
+    overrides: AHashSet<ModuleKey>,
+    infos: Vec<ModuleInfo>,
+}
+
+impl Default for ModuleProvider {
+    fn default() -> Self {
+        ModuleProvider::new()
+    }
+}
 
 impl ModuleProvider {
     pub fn new() -> Self {
-        ModuleProvider(AHashMap::new(), vec![])
+        ModuleProvider {
+            modules: AHashMap::new(),
+            registers: vec![],
+            overrides: AHashSet::new(),
+            infos: vec![],
+        }
+    }
+
+    /// 基于模块容器创建模块注册器的快照，用于在启动后执行的工厂方法
+    pub(crate) fn snapshot(container: &ModuleContainer) -> Self {
+        let mut modules = container.modules.as_ref().clone();
+        let snapshots = modules.values()
+            .filter_map(|boxed| boxed.downcast_ref::<Module<Snapshot>>())
+            .map(|snapshot| (snapshot.0).0())
+            .collect::<Vec<_>>();
+        modules.extend(snapshots);
+
+        ModuleProvider {
+            modules,
+            registers: vec![],
+            overrides: AHashSet::new(),
+            infos: vec![],
+        }
     }
 
     pub fn initialize<T>(init_obj: T) -> Self
@@ -122,25 +510,56 @@ impl ModuleProvider {
     pub fn insert<T>(&mut self, obj: T)
         where T: Send + Sync + Clone + 'static
     {
+        if self.is_overridden(&ModuleKey::of::<T>()) {
+            return;
+        }
+
         info!("Register module [{}]", type_name::<T>());
-        self.0.insert(TypeId::of::<T>(), Box::new(Module(obj.clone())));
-        self.1.push(StandardModuleRegister::boxed(obj));
+        inspect::record(&mut self.infos, ModuleInfo::new(type_name::<T>(), None, ModuleKind::Eager));
+        self.modules.insert(ModuleKey::of::<T>(), Arc::new(Module(obj.clone())));
+        self.registers.push(StandardModuleRegister::boxed(obj));
+    }
+
+    /// 写入具名模块
+    ///
+    /// 同一类型的模块可以以不同的名称注册多个实例，例如主库与分析库两个连接池。
+    /// 具名模块不会以 `Data<T>` 的形式注册到 actix web 中，需要通过
+    /// [`Named`](crate::service::Named) 注入或 [`ModuleContainer::get_named`] 获取。
+    pub fn insert_named<T>(&mut self, name: &str, obj: T)
+        where T: Send + Sync + Clone + 'static
+    {
+        self.insert_named_with_hooks(name, obj, ModuleHooks::default())
     }
 
     pub fn get<T>(&self) -> Option<T>
         where T: Send + Sync + Clone + 'static
     {
-        self.0
-            .get(&TypeId::of::<T>())
-            .and_then(|boxed| boxed.downcast_ref::<Module<T>>())
-            .map(|obj| obj.0.clone())
+        self.get_ref::<T>().cloned()
     }
 
     pub fn get_ref<T>(&self) -> Option<&T>
         where T: Send + Sync + Clone + 'static
     {
-        self.0
-            .get(&TypeId::of::<T>())
+        self.get_by_key(&ModuleKey::of::<T>())
+    }
+
+    pub fn get_named<T>(&self, name: &str) -> Option<T>
+        where T: Send + Sync + Clone + 'static
+    {
+        self.get_named_ref::<T>(name).cloned()
+    }
+
+    pub fn get_named_ref<T>(&self, name: &str) -> Option<&T>
+        where T: Send + Sync + Clone + 'static
+    {
+        self.get_by_key(&ModuleKey::named::<T>(name))
+    }
+
+    fn get_by_key<T>(&self, key: &ModuleKey) -> Option<&T>
+        where T: Send + Sync + Clone + 'static
+    {
+        self.modules
+            .get(key)
             .and_then(|boxed| boxed.downcast_ref::<Module<T>>())
             .map(|obj| &obj.0)
     }
@@ -148,94 +567,961 @@ impl ModuleProvider {
     pub fn contains<T>(&self) -> bool
         where T: Send + Sync + Clone + 'static
     {
-        self.0
-            .contains_key(&TypeId::of::<T>())
+        self.modules
+            .contains_key(&ModuleKey::of::<T>())
     }
 
-    pub fn clear(&mut self) {
-        self.0.clear();
-        self.1.clear();
+    pub(crate) fn contains_key(&self, key: &ModuleKey) -> bool {
+        self.modules.contains_key(key)
     }
 
-    pub async fn register<T, F, E>(&mut self, factory: F) -> anyhow::Result<()>
-        where
-                for<'a> F: ModuleFactoryFn<'a, T, E>,
-                T: Send + Sync + Clone + 'static,
-                E: std::error::Error + Send + Sync + 'static,
+    pub fn contains_named<T>(&self, name: &str) -> bool
+        where T: Send + Sync + Clone + 'static
     {
-        let result = factory.call(self).await?;
-        self.insert(result);
-        Ok(())
+        self.modules
+            .contains_key(&ModuleKey::named::<T>(name))
     }
 
-    pub fn into_module_container(self) -> ModuleContainer {
-        ModuleContainer::new(self.1)
+    pub fn clear(&mut self) {
+        self.modules.clear();
+        self.registers.clear();
+        self.overrides.clear();
+        self.infos.clear();
     }
-}
 
-pub trait ModuleFactoryFn<'a, T, E>
-    where T: Send + Sync + Clone + 'static,
-          E: std::error::Error + Send + Sync
-{
-    type Res: Future<Output=Result<T, E>>;
-    fn call(self, s: &'a ModuleProvider) -> Self::Res;
-}
-
-impl<'a, T, F, E, R> ModuleFactoryFn<'a, T, E> for F
-    where F: Fn(&'a ModuleProvider) -> R,
-          R: Future<Output=Result<T, E>> + 'a,
-          T: Send + Sync + Clone + 'static,
-          E: std::error::Error + Send + Sync
-{
-    type Res = R;
+    /// 覆盖模块
+    ///
+    /// 立即以 `obj` 注册（或替换已注册的）模块 `T`，此后对 `T` 的写入及注册均被忽略，
+    /// 其工厂方法也不会被执行。用于在测试中以测试替身替换真实的模块，
+    /// 例如在不修改应用启动流程的情况下替换数据库连接池或邮件发送器：
+    ///
+    /// ```
+    /// use inspirer_actix_ext_core::error::BootstrapError;
+    /// use inspirer_actix_ext_core::module::ModuleProvider;
+    ///
+    /// async fn connect(_: &ModuleProvider) -> std::io::Result<String> {
+    ///     unreachable!("factory of overridden module will not be called")
+    /// }
+    ///
+    /// async fn bootstrap(module_provider: &mut ModuleProvider) -> Result<(), BootstrapError> {
+    ///     module_provider.register(connect).await
+    /// }
+    ///
+    /// # futures::executor::block_on(async {
+    /// let mut module_provider = ModuleProvider::new();
+    /// module_provider.override_with("fake connection".to_string());
+    /// bootstrap(&mut module_provider).await.unwrap();
+    ///
+    /// assert_eq!("fake connection", module_provider.get::<String>().unwrap());
+    /// # });
+    /// ```
+    ///
+    /// 被覆盖的模块不会附带原有的生命周期钩子及健康探针。
+    pub fn override_with<T>(&mut self, obj: T)
+        where T: Send + Sync + Clone + 'static
+    {
+        let key = ModuleKey::of::<T>();
+        info!("Override module [{}]", type_name::<T>());
 
-    fn call(self, s: &'a ModuleProvider) -> Self::Res {
-        self(s)
+        self.modules.insert(key.clone(), Arc::new(Module(obj.clone())));
+        replace_register(&mut self.registers, &key, StandardModuleRegister::boxed(obj));
+        inspect::mark_overridden(&mut self.infos, type_name::<T>(), None);
+        self.overrides.insert(key);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_insert() {
-        let mut module_provider = ModuleProvider::new();
-        module_provider.insert(1u8);
-        module_provider.insert(2u16);
-        module_provider.insert(4u32);
-        module_provider.insert(8u64);
+    /// 覆盖具名模块
+    pub fn override_named_with<T>(&mut self, name: &str, obj: T)
+        where T: Send + Sync + Clone + 'static
+    {
+        let key = ModuleKey::named::<T>(name);
+        info!("Override module [{}]", display_name(type_name::<T>(), Some(name)));
 
-        assert!(module_provider.contains::<u8>());
-        assert!(module_provider.contains::<u16>());
-        assert!(module_provider.contains::<u32>());
-        assert!(module_provider.contains::<u64>());
+        self.modules.insert(key.clone(), Arc::new(Module(obj.clone())));
+        replace_register(&mut self.registers, &key, LifecycleModuleRegister::named(name, obj, ModuleHooks::default()));
+        inspect::mark_overridden(&mut self.infos, type_name::<T>(), Some(name));
+        self.overrides.insert(key);
+    }
 
-        assert_eq!(1, module_provider.get::<u8>().unwrap());
-        assert_eq!(2, module_provider.get::<u16>().unwrap());
-        assert_eq!(4, module_provider.get::<u32>().unwrap());
-        assert_eq!(8, module_provider.get::<u64>().unwrap());
+    pub(crate) fn is_overridden(&self, key: &ModuleKey) -> bool {
+        let overridden = self.overrides.contains(key);
+        if overridden {
+            debug!("Module [{:?}] is overridden, skip registration.", key);
+        }
+        overridden
     }
 
-    #[test]
-    fn test_clear() {
-        let mut module_provider = ModuleProvider::new();
-        module_provider.insert(1u8);
-        module_provider.insert(2u16);
-        module_provider.insert(4u32);
+    /// 写入带有生命周期钩子的模块
+    pub fn insert_with_hooks<T>(&mut self, obj: T, hooks: ModuleHooks<T>)
+        where T: Send + Sync + Clone + 'static
+    {
+        if hooks.is_empty() {
+            return self.insert(obj);
+        }
 
-        assert!(module_provider.contains::<u8>());
-        assert!(module_provider.contains::<u16>());
-        assert!(module_provider.contains::<u32>());
+        if self.is_overridden(&ModuleKey::of::<T>()) {
+            return;
+        }
 
-        module_provider.clear();
+        info!("Register module [{}] with lifecycle hooks", type_name::<T>());
+        inspect::record(&mut self.infos, ModuleInfo::new(type_name::<T>(), None, ModuleKind::Eager));
+        self.modules.insert(ModuleKey::of::<T>(), Arc::new(Module(obj.clone())));
+        self.registers.push(LifecycleModuleRegister::boxed(obj, hooks));
+    }
 
-        assert!(!module_provider.contains::<u8>());
-        assert!(!module_provider.contains::<u16>());
-        assert!(!module_provider.contains::<u32>());
+    /// 写入带有生命周期钩子的具名模块
+    pub fn insert_named_with_hooks<T>(&mut self, name: &str, obj: T, hooks: ModuleHooks<T>)
+        where T: Send + Sync + Clone + 'static
+    {
+        if self.is_overridden(&ModuleKey::named::<T>(name)) {
+            return;
+        }
+
+        info!("Register module [{}]", display_name(type_name::<T>(), Some(name)));
+        inspect::record(&mut self.infos, ModuleInfo::new(type_name::<T>(), Some(name), ModuleKind::Eager));
+        self.modules.insert(ModuleKey::named::<T>(name), Arc::new(Module(obj.clone())));
+        self.registers.push(LifecycleModuleRegister::named(name, obj, hooks));
     }
 
-    #[tokio::test]
+    pub async fn register<T, F, E>(&mut self, factory: F) -> Result<(), BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E>,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        self.register_with_hooks(factory, ModuleHooks::default()).await
+    }
+
+    /// 通过工厂方法注册带有生命周期钩子的模块
+    pub async fn register_with_hooks<T, F, E>(&mut self, factory: F, hooks: ModuleHooks<T>) -> Result<(), BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E>,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        if let Some((obj, elapsed)) = self.create(&ModuleKey::of::<T>(), factory).await? {
+            self.install(None, obj, hooks, elapsed);
+        }
+        Ok(())
+    }
+
+    /// 满足条件时通过工厂方法注册模块，否则忽略，详见 [`condition`](crate::condition) 模块
+    pub async fn register_if<T, F, E>(&mut self, condition: Condition, factory: F) -> Result<(), BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E>,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        if !condition.matches(self) {
+            info!("Skip module [{}], condition is not met: {}", type_name::<T>(), condition);
+            return Ok(());
+        }
+
+        self.register(factory).await
+    }
+
+    /// 通过工厂方法注册模块，失败时按重试策略重新执行，详见 [`retry`](crate::retry) 模块
+    pub async fn register_with_retry<T, F, E>(&mut self, factory: F, policy: RetryPolicy) -> Result<(), BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E> + Clone,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        if let Some((obj, elapsed)) = self.create_with_retry(&ModuleKey::of::<T>(), factory, &policy).await? {
+            self.install(None, obj, ModuleHooks::default(), elapsed);
+        }
+        Ok(())
+    }
+
+    /// 通过工厂方法注册具名模块
+    pub async fn register_named<T, F, E>(&mut self, name: &str, factory: F) -> Result<(), BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E>,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        self.register_named_with_hooks(name, factory, ModuleHooks::default()).await
+    }
+
+    /// 通过工厂方法注册带有生命周期钩子的具名模块
+    pub async fn register_named_with_hooks<T, F, E>(&mut self, name: &str, factory: F, hooks: ModuleHooks<T>) -> Result<(), BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E>,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        if let Some((obj, elapsed)) = self.create(&ModuleKey::named::<T>(name), factory).await? {
+            self.install(Some(name), obj, hooks, elapsed);
+        }
+        Ok(())
+    }
+
+    /// 通过工厂方法注册具名模块，失败时按重试策略重新执行
+    pub async fn register_named_with_retry<T, F, E>(&mut self, name: &str, factory: F, policy: RetryPolicy) -> Result<(), BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E> + Clone,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        if let Some((obj, elapsed)) = self.create_with_retry(&ModuleKey::named::<T>(name), factory, &policy).await? {
+            self.install(Some(name), obj, ModuleHooks::default(), elapsed);
+        }
+        Ok(())
+    }
+
+    /// 执行工厂方法，模块已被覆盖时不执行并返回 `None`
+    async fn create<T, F, E>(&self, key: &ModuleKey, factory: F) -> Result<Option<(T, Duration)>, BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E>,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        if self.is_overridden(key) {
+            return Ok(None);
+        }
+
+        let start = Instant::now();
+        let obj = factory.call(self).await
+            .map_err(|err| BootstrapError::from_factory(display_name(type_name::<T>(), key.name.as_deref()), err))?;
+        Ok(Some((obj, start.elapsed())))
+    }
+
+    /// 按重试策略执行工厂方法
+    ///
+    /// 仅工厂方法本身的错误（[`BootstrapError::FactoryFailed`]）会触发重试，记录的耗时包含所有重试及等待时间。
+    async fn create_with_retry<T, F, E>(&self, key: &ModuleKey, factory: F, policy: &RetryPolicy) -> Result<Option<(T, Duration)>, BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E> + Clone,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        let module = display_name(type_name::<T>(), key.name.as_deref());
+        let start = Instant::now();
+
+        let attempts = async {
+            let mut attempt = 1;
+            loop {
+                match self.create(key, factory.clone()).await {
+                    Ok(created) => return Ok(created.map(|(obj, _)| (obj, start.elapsed()))),
+                    Err(err @ BootstrapError::FactoryFailed { .. }) if attempt < policy.max_attempts => {
+                        let backoff = policy.backoff_for(attempt);
+                        warn!("Module [{}] initialize failed (attempt {}/{}): {}, retry after {:?}.", module, attempt, policy.max_attempts, err, backoff);
+
+                        Delay::new(backoff).await;
+                        attempt += 1;
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+        };
+
+        match policy.timeout {
+            Some(timeout) => match select(Box::pin(attempts), Delay::new(timeout)).await {
+                Either::Left((result, _)) => result,
+                Either::Right(_) => Err(BootstrapError::Timeout {
+                    module: display_name(type_name::<T>(), key.name.as_deref()),
+                    timeout,
+                }),
+            },
+            None => attempts.await,
+        }
+    }
+
+    fn install<T>(&mut self, name: Option<&str>, obj: T, hooks: ModuleHooks<T>, elapsed: Duration)
+        where T: Send + Sync + Clone + 'static
+    {
+        match name {
+            Some(name) => self.insert_named_with_hooks(name, obj, hooks),
+            None => self.insert_with_hooks(obj, hooks),
+        }
+        self.describe(type_name::<T>(), name, |info| info.factory_duration = Some(elapsed));
+    }
+
+    /// 注册延迟初始化的模块
+    ///
+    /// 工厂方法不会立即执行，而是在首次通过 [`Lazy`](crate::lazy::Lazy) 或
+    /// [`ModuleContainer::get_lazy`] 获取模块时执行，详见 [`lazy`](crate::lazy) 模块。
+    pub fn register_lazy<T, F, E>(&mut self, factory: F)
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E> + Clone + Send + Sync + 'static,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        info!("Register lazy module [{}]", type_name::<T>());
+        let module = match self.get::<T>() {
+            Some(obj) if self.is_overridden(&ModuleKey::of::<T>()) => Arc::new(LazyModule::initialized(obj)),
+            _ => Arc::new(LazyModule::new(factory)),
+        };
+        let mut info = ModuleInfo::new(type_name::<T>(), None, ModuleKind::Lazy);
+        info.overridden = self.overrides.contains(&ModuleKey::of::<T>());
+        inspect::record(&mut self.infos, info);
+
+        self.modules.insert(ModuleKey::of::<LazyModule<T>>(), module.clone());
+        self.registers.push(Box::new(LazyModuleRegister(module)));
+    }
+
+    /// 注册请求作用域的模块
+    ///
+    /// 工厂方法在请求中首次解析该模块时执行，结果在该请求的生命周期内缓存，
+    /// 详见 [`scope`](crate::scope) 模块。
+    pub fn register_scoped<T, F, E>(&mut self, factory: F)
+        where
+                F: Fn(&HttpRequest) -> Result<T, E> + Send + Sync + 'static,
+                T: Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        info!("Register request scoped module [{}]", type_name::<T>());
+        inspect::record(&mut self.infos, ModuleInfo::new(type_name::<T>(), None, ModuleKind::Scoped));
+        self.registers.push(Box::new(ScopedModuleRegister(Arc::new(ScopedFactory::new(factory)))));
+    }
+
+    /// 注册异步创建的请求作用域模块
+    ///
+    /// 适用于需要异步创建或无法 `Clone` 的模块，例如数据库事务。模块以 [`Scoped<T>`] 句柄共享，
+    /// 详见 [`scope`](crate::scope) 模块。
+    pub fn register_scoped_async<T, F, E>(&mut self, factory: F)
+        where
+                F: Fn(&HttpRequest) -> LocalBoxFuture<'static, Result<T, E>> + Send + Sync + 'static,
+                T: 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        info!("Register request scoped module [{}]", type_name::<Scoped<T>>());
+        inspect::record(&mut self.infos, ModuleInfo::new(type_name::<Scoped<T>>(), None, ModuleKind::Scoped));
+        self.registers.push(Box::new(AsyncScopedModuleRegister(Arc::new(AsyncScopedFactory::new(factory)))));
+    }
+
+    /// 注册功能模块
+    ///
+    /// 若注册器中存在 `Config` 模块，将按其中的 `modules.{name}` 配置节决定是否启用及挂载前缀，
+    /// 因此需要在配置模块之后注册。未启用的功能模块将被忽略。
+    pub fn insert_feature<F: FeatureModule>(&mut self, feature: F) -> Result<(), BootstrapError> {
+        let config = match self.get_ref::<Config>() {
+            Some(config) => FeatureConfig::load(config, feature.name())
+                .map_err(|source| BootstrapError::ConfigInvalid {
+                    module: display_name(type_name::<F>(), Some(feature.name())),
+                    key: format!("modules.{}", feature.name()),
+                    source: Box::new(source),
+                })?,
+            None => FeatureConfig::default(),
+        };
+
+        if !config.enabled {
+            info!("Feature module [{}] is disabled, skip registration.", feature.name());
+            return Ok(());
+        }
+
+        let prefix = config.prefix.unwrap_or_else(|| feature.prefix().to_string());
+        info!("Register feature module [{}] at [{}]", feature.name(), prefix);
+
+        inspect::record(&mut self.infos, ModuleInfo::new(type_name::<F>(), Some(feature.name()), ModuleKind::Feature));
+        self.registers.push(Box::new(FeatureModuleRegister { feature, prefix }));
+
+        Ok(())
+    }
+
+    /// 注册后台任务
+    ///
+    /// 任务在应用服务启动后运行，失败或 panic 时按重启策略重启，应用停止时取消，
+    /// 详见 [`task`](crate::task) 模块。
+    pub fn register_task(&mut self, task: BackgroundTask) {
+        info!("Register background task [{}]", task.name());
+        inspect::record(&mut self.infos, ModuleInfo::new(type_name::<BackgroundTask>(), Some(task.name()), ModuleKind::Task));
+        self.registers.push(Box::new(TaskModuleRegister(Arc::new(task))));
+    }
+
+    /// 写入可热替换的模块，返回模块句柄
+    ///
+    /// 模块以 [`Reloadable<T>`] 的形式注册，同时服务可以直接依赖 `T`，其他模块的工厂方法也可以直接获取 `T`，
+    /// 详见 [`reload`](crate::reload) 模块。模块已被覆盖时返回包装覆盖实例的句柄，该模块不会再被替换。
+    pub fn insert_reloadable<T>(&mut self, obj: T) -> Reloadable<T>
+        where T: Send + Sync + Clone + 'static
+    {
+        if let Some(reloadable) = self.overridden_reloadable::<T>() {
+            return reloadable;
+        }
+
+        info!("Register reloadable module [{}]", type_name::<T>());
+        inspect::record(&mut self.infos, ModuleInfo::new(type_name::<T>(), None, ModuleKind::Eager));
+
+        let reloadable = Reloadable::new(obj.clone());
+        self.modules.insert(ModuleKey::of::<Reloadable<T>>(), Arc::new(Module(reloadable.clone())));
+        self.registers.push(StandardModuleRegister::boxed(reloadable.clone()));
+
+        let current = reloadable.clone();
+        let factory = ScopedFactory::new(move |_| Ok::<_, Infallible>(current.get().as_ref().clone()));
+        self.registers.push(Box::new(ScopedModuleRegister(Arc::new(factory))));
+
+        // 其他模块的工厂方法读取的快照，基于模块容器执行工厂方法时刷新为当前实例
+        self.modules.insert(ModuleKey::of::<T>(), Arc::new(Module(obj)));
+        let current = reloadable.clone();
+        let snapshot = Snapshot(Arc::new(move || (ModuleKey::of::<T>(), Arc::new(Module(current.get().as_ref().clone())))));
+        self.modules.insert(ModuleKey::named::<Snapshot>(type_name::<T>()), Arc::new(Module(snapshot)));
+
+        reloadable
+    }
+
+    /// 通过工厂方法注册可热替换的模块，模块已被覆盖时不执行工厂方法
+    pub async fn register_reloadable<T, F, E>(&mut self, factory: F) -> Result<Reloadable<T>, BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E>,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        let created = self.create(&ModuleKey::of::<T>(), factory).await?;
+        Ok(self.install_reloadable(created))
+    }
+
+    /// 通过工厂方法注册可热替换的模块，失败时按重试策略重新执行
+    pub async fn register_reloadable_with_retry<T, F, E>(&mut self, factory: F, policy: RetryPolicy) -> Result<Reloadable<T>, BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E> + Clone,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        let created = self.create_with_retry(&ModuleKey::of::<T>(), factory, &policy).await?;
+        Ok(self.install_reloadable(created))
+    }
+
+    fn install_reloadable<T>(&mut self, created: Option<(T, Duration)>) -> Reloadable<T>
+        where T: Send + Sync + Clone + 'static
+    {
+        match created {
+            Some((obj, elapsed)) => {
+                let reloadable = self.insert_reloadable(obj);
+                self.describe(type_name::<T>(), None, |info| info.factory_duration = Some(elapsed));
+                reloadable
+            }
+            None => self.overridden_reloadable::<T>().expect("module is overridden"),
+        }
+    }
+
+    /// 模块已被覆盖时，注册并返回包装覆盖实例的句柄
+    fn overridden_reloadable<T>(&mut self) -> Option<Reloadable<T>>
+        where T: Send + Sync + Clone + 'static
+    {
+        if !self.is_overridden(&ModuleKey::of::<T>()) {
+            return None;
+        }
+
+        let reloadable = Reloadable::new(self.get::<T>()?);
+        self.modules.insert(ModuleKey::of::<Reloadable<T>>(), Arc::new(Module(reloadable.clone())));
+        self.registers.push(StandardModuleRegister::boxed(reloadable.clone()));
+        Some(reloadable)
+    }
+
+    /// 按依赖顺序批量注册模块
+    ///
+    /// 注册前会根据模块定义所声明的依赖进行拓扑排序，若存在缺失的依赖或循环依赖，
+    /// 将直接返回错误而不会执行任何工厂方法。已经注册在当前注册器中的模块视为已满足的依赖。
+    ///
+    /// 排序后将集中执行全部模块定义的配置预检（参见 [`ModuleDefinition::config_check`]），
+    /// 存在失败的预检时一并返回，同样不会执行任何工厂方法。
+    pub async fn register_all(&mut self, definitions: Vec<ModuleDefinition>) -> Result<(), BootstrapError> {
+        let definitions = self.sort_definitions(definitions)?;
+        self.check_definitions(&definitions)?;
+
+        for (definition, _) in definitions {
+            if !definition.is_satisfied(self) {
+                continue;
+            }
+
+            debug!("Register module [{}] by definition.", definition.display_name());
+            let (key, type_name, dependencies) = self.definition_info(&definition);
+            let policy = definition.retry_policy(self)?;
+
+            let install = (definition.factory)(self, policy).await?;
+            install(self);
+            self.describe(type_name, key.name.as_deref(), |info| info.dependencies = dependencies);
+        }
+
+        Ok(())
+    }
+
+    /// 按依赖顺序批量注册模块，并发执行相互独立的工厂方法
+    ///
+    /// 与 [`register_all`](ModuleProvider::register_all) 相同，注册前会根据模块定义所声明的依赖进行拓扑排序并执行配置预检，
+    /// 随后按依赖层级分批注册：同一批中的模块相互之间没有依赖，其工厂方法将并发执行，
+    /// 全部完成后再统一写入注册器，因此后续批次的工厂方法总能获取到先前批次的模块。
+    ///
+    /// 由于并发执行，模块之间的依赖必须通过 [`ModuleDefinition::depends_on`] 或 [`ModuleDefinition::after`]
+    /// 完整声明，而不能依赖定义的先后顺序。
+    ///
+    /// 某一批中存在工厂方法执行失败时，该批中执行成功的模块仍会被写入注册器，但不再继续注册后续批次，
+    /// 并以 [`BootstrapError::ModulesInitFailed`] 汇总返回该批中所有失败的模块及原因。
+    pub async fn register_all_concurrent(&mut self, definitions: Vec<ModuleDefinition>) -> Result<(), BootstrapError> {
+        let definitions = self.sort_definitions(definitions)?;
+        self.check_definitions(&definitions)?;
+
+        let mut batches: Vec<Vec<ModuleDefinition>> = vec![];
+        for (definition, level) in definitions {
+            match batches.get_mut(level) {
+                Some(batch) => batch.push(definition),
+                None => batches.push(vec![definition]),
+            }
+        }
+
+        for batch in batches {
+            let batch = batch.into_iter()
+                .filter(|definition| definition.is_satisfied(self))
+                .collect::<Vec<_>>();
+
+            debug!("Register modules [{}] concurrently.", batch.iter().map(ModuleDefinition::display_name).collect::<Vec<_>>().join(", "));
+            let infos = batch.iter().map(|definition| self.definition_info(definition)).collect::<Vec<_>>();
+            let policies = batch.iter().map(|definition| definition.retry_policy(self)).collect::<Result<Vec<_>, _>>()?;
+
+            let provider: &ModuleProvider = self;
+            let results = join_all(batch.into_iter().zip(policies).map(|(definition, policy)| (definition.factory)(provider, policy))).await;
+
+            let mut failures = vec![];
+            for ((key, type_name, dependencies), result) in infos.into_iter().zip(results) {
+                match result {
+                    Ok(install) => {
+                        install(self);
+                        self.describe(type_name, key.name.as_deref(), |info| info.dependencies = dependencies);
+                    }
+                    Err(err) => failures.push(err),
+                }
+            }
+
+            if !failures.is_empty() {
+                return Err(BootstrapError::ModulesInitFailed(failures));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 执行全部模块定义的配置预检，存在失败的预检时汇总返回
+    ///
+    /// 配置预检依赖 `Config` 模块，尚未注册 `Config` 模块（例如其本身在本次批量注册中）时跳过预检。
+    fn check_definitions(&self, definitions: &[(ModuleDefinition, usize)]) -> Result<(), BootstrapError> {
+        if self.get_ref::<Config>().is_none() {
+            return Ok(());
+        }
+
+        let mut failures = definitions.iter()
+            .flat_map(|(definition, _)| definition.check_config(self))
+            .collect::<Vec<_>>();
+        match failures.len() {
+            0 => Ok(()),
+            1 => Err(failures.remove(0)),
+            _ => Err(BootstrapError::ModulesInitFailed(failures)),
+        }
+    }
+
+    fn definition_info(&self, definition: &ModuleDefinition) -> (ModuleKey, &'static str, Vec<String>) {
+        let dependencies = definition.dependencies.iter()
+            .filter(|dependency| dependency.required || self.modules.contains_key(&dependency.key))
+            .map(|dependency| display_name(dependency.type_name, dependency.key.name.as_deref()))
+            .collect();
+
+        (definition.key.clone(), definition.type_name, dependencies)
+    }
+
+    fn describe<F>(&mut self, module: &str, name: Option<&str>, f: F)
+        where F: FnOnce(&mut ModuleInfo)
+    {
+        if let Some(info) = self.infos.iter_mut().find(|info| info.is(module, name)) {
+            f(info)
+        }
+    }
+
+    /// 对模块定义进行拓扑排序，同时返回各定义的依赖层级（不依赖本批其他定义的层级为 0）
+    fn sort_definitions(&self, definitions: Vec<ModuleDefinition>) -> Result<Vec<(ModuleDefinition, usize)>, BootstrapError> {
+        let mut provided = AHashMap::new();
+        for (index, definition) in definitions.iter().enumerate() {
+            provided.insert(definition.key.clone(), index);
+        }
+
+        let mut edges = Vec::with_capacity(definitions.len());
+        for definition in definitions.iter() {
+            let mut dependencies = vec![];
+            for dependency in definition.dependencies.iter() {
+                match provided.get(&dependency.key) {
+                    Some(index) => dependencies.push(*index),
+                    None if !dependency.required || self.modules.contains_key(&dependency.key) => (),
+                    None => return Err(BootstrapError::MissingDependency {
+                        module: definition.display_name(),
+                        dependency: display_name(dependency.type_name, dependency.key.name.as_deref()),
+                    }),
+                }
+            }
+            edges.push(dependencies);
+        }
+
+        let mut sorted = Vec::with_capacity(definitions.len());
+        let mut visited = vec![false; definitions.len()];
+        while sorted.len() < definitions.len() {
+            let next = (0..definitions.len())
+                .find(|index| !visited[*index] && edges[*index].iter().all(|dep| visited[*dep]));
+
+            match next {
+                Some(index) => {
+                    visited[index] = true;
+                    sorted.push(index);
+                }
+                None => {
+                    let cycle = find_cycle(&edges, &visited)
+                        .into_iter()
+                        .map(|index| definitions[index].display_name())
+                        .collect::<Vec<_>>()
+                        .join(" -> ");
+
+                    return Err(BootstrapError::CircularDependency(cycle));
+                }
+            }
+        }
+
+        let mut levels = vec![0; definitions.len()];
+        for index in sorted.iter() {
+            levels[*index] = edges[*index].iter().map(|dep| levels[*dep] + 1).max().unwrap_or(0);
+        }
+
+        let mut definitions = definitions.into_iter().map(Some).collect::<Vec<_>>();
+        Ok(sorted.into_iter().filter_map(|index| definitions[index].take().map(|definition| (definition, levels[index]))).collect())
+    }
+
+    pub fn into_module_container(self) -> ModuleContainer {
+        ModuleContainer::new(self.registers, self.modules, self.infos)
+    }
+}
+
+pub trait ModuleFactoryFn<'a, T, E>
+    where T: Send + Sync + Clone + 'static,
+          E: std::error::Error + Send + Sync
+{
+    type Res: Future<Output=Result<T, E>> + 'a;
+    fn call(self, s: &'a ModuleProvider) -> Self::Res;
+}
+
+impl<'a, T, F, E, R> ModuleFactoryFn<'a, T, E> for F
+    where F: Fn(&'a ModuleProvider) -> R,
+          R: Future<Output=Result<T, E>> + 'a,
+          T: Send + Sync + Clone + 'static,
+          E: std::error::Error + Send + Sync
+{
+    type Res = R;
+
+    fn call(self, s: &'a ModuleProvider) -> Self::Res {
+        self(s)
+    }
+}
+
+/// 从尚未完成排序的定义中找出一条依赖环路，返回环路上的定义下标（首尾相同）
+fn find_cycle(edges: &[Vec<usize>], sorted: &[bool]) -> Vec<usize> {
+    let start = match (0..edges.len()).find(|index| !sorted[*index]) {
+        Some(start) => start,
+        None => return vec![],
+    };
+
+    let mut path = vec![start];
+    let mut current = start;
+    loop {
+        // 未排序的节点必然至少存在一个未排序的依赖，沿着它走下去终会回到路径上的某个节点
+        current = match edges[current].iter().find(|dep| !sorted[**dep]) {
+            Some(next) => *next,
+            None => return path,
+        };
+
+        if let Some(position) = path.iter().position(|index| *index == current) {
+            let mut cycle = path.split_off(position);
+            cycle.push(current);
+            return cycle;
+        }
+
+        path.push(current);
+    }
+}
+
+struct DependencyDefinition {
+    key: ModuleKey,
+    type_name: &'static str,
+    required: bool,
+}
+
+/// 写入模块，仅在工厂方法执行完成后调用
+type DefinitionInstaller = Box<dyn FnOnce(&mut ModuleProvider)>;
+
+type DefinitionFactory = Box<dyn for<'a> FnOnce(&'a ModuleProvider, RetryPolicy) -> LocalBoxFuture<'a, Result<DefinitionInstaller, BootstrapError>>>;
+
+/// 配置预检，参见 [`ModuleDefinition::config_check`]
+type ConfigCheck = Box<dyn Fn(&ModuleProvider) -> Result<(), BootstrapError>>;
+
+/// 模块定义的重试策略来源
+enum DefinitionRetry {
+    Policy(RetryPolicy),
+    Config(String),
+}
+
+/// 模块定义
+///
+/// 描述一个模块的工厂方法及其依赖的模块，通过 [`ModuleProvider::register_all`] 注册时，
+/// 注册器会保证其依赖的模块先于它完成注册。
+pub struct ModuleDefinition {
+    key: ModuleKey,
+    type_name: &'static str,
+    dependencies: Vec<DependencyDefinition>,
+    condition: Option<Condition>,
+    retry: DefinitionRetry,
+    config_checks: Vec<ConfigCheck>,
+    factory: DefinitionFactory,
+}
+
+impl ModuleDefinition {
+    pub fn new<T, F, E>(factory: F) -> Self
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E> + Clone + 'static,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        Self::with_hooks(factory, ModuleHooks::default())
+    }
+
+    /// 创建带有生命周期钩子的模块定义
+    pub fn with_hooks<T, F, E>(factory: F, hooks: ModuleHooks<T>) -> Self
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E> + Clone + 'static,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        ModuleDefinition {
+            key: ModuleKey::of::<T>(),
+            type_name: type_name::<T>(),
+            dependencies: vec![],
+            condition: None,
+            retry: DefinitionRetry::Policy(RetryPolicy::default()),
+            config_checks: vec![],
+            factory: Box::new(move |provider, policy| Box::pin(async move {
+                let installer: DefinitionInstaller = match provider.create_with_retry(&ModuleKey::of::<T>(), factory, &policy).await? {
+                    Some((obj, elapsed)) => Box::new(move |provider| provider.install(None, obj, hooks, elapsed)),
+                    None => Box::new(|_| ()),
+                };
+                Ok(installer)
+            })),
+        }
+    }
+
+    /// 创建具名模块定义
+    pub fn named<T, F, E>(name: &str, factory: F) -> Self
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E> + Clone + 'static,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        Self::named_with_hooks(name, factory, ModuleHooks::default())
+    }
+
+    /// 创建带有生命周期钩子的具名模块定义
+    pub fn named_with_hooks<T, F, E>(name: &str, factory: F, hooks: ModuleHooks<T>) -> Self
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E> + Clone + 'static,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        let module_name = name.to_string();
+        ModuleDefinition {
+            key: ModuleKey::named::<T>(name),
+            type_name: type_name::<T>(),
+            dependencies: vec![],
+            condition: None,
+            retry: DefinitionRetry::Policy(RetryPolicy::default()),
+            config_checks: vec![],
+            factory: Box::new(move |provider, policy| Box::pin(async move {
+                let installer: DefinitionInstaller = match provider.create_with_retry(&ModuleKey::named::<T>(&module_name), factory, &policy).await? {
+                    Some((obj, elapsed)) => Box::new(move |provider| provider.install(Some(&module_name), obj, hooks, elapsed)),
+                    None => Box::new(|_| ()),
+                };
+                Ok(installer)
+            })),
+        }
+    }
+
+    /// 声明必需的依赖模块
+    ///
+    /// 若该模块既不在本次批量注册中，也未在注册器中注册，则批量注册失败。
+    pub fn depends_on<T>(mut self) -> Self
+        where T: Send + Sync + Clone + 'static
+    {
+        self.dependencies.push(DependencyDefinition {
+            key: ModuleKey::of::<T>(),
+            type_name: type_name::<T>(),
+            required: true,
+        });
+        self
+    }
+
+    /// 声明必需的具名依赖模块
+    pub fn depends_on_named<T>(mut self, name: &str) -> Self
+        where T: Send + Sync + Clone + 'static
+    {
+        self.dependencies.push(DependencyDefinition {
+            key: ModuleKey::named::<T>(name),
+            type_name: type_name::<T>(),
+            required: true,
+        });
+        self
+    }
+
+    /// 声明可选的依赖模块
+    ///
+    /// 若该模块在本次批量注册中，则保证其先于当前模块注册，否则忽略。适用于
+    /// 工厂方法存在多个备选依赖来源的情况，例如优先读取 `DatabaseConfig`，其次读取 `Config`。
+    pub fn after<T>(mut self) -> Self
+        where T: Send + Sync + Clone + 'static
+    {
+        self.dependencies.push(DependencyDefinition {
+            key: ModuleKey::of::<T>(),
+            type_name: type_name::<T>(),
+            required: false,
+        });
+        self
+    }
+
+    /// 声明可选的具名依赖模块，参见 [`ModuleDefinition::after`]
+    pub fn after_named<T>(mut self, name: &str) -> Self
+        where T: Send + Sync + Clone + 'static
+    {
+        self.dependencies.push(DependencyDefinition {
+            key: ModuleKey::named::<T>(name),
+            type_name: type_name::<T>(),
+            required: false,
+        });
+        self
+    }
+
+    /// 设置注册条件，不满足时忽略该模块，多次设置时需同时满足
+    ///
+    /// 条件中引用的模块（如 [`Condition::module_present`]）若在本次批量注册中，将保证其先于当前模块注册。
+    /// 注意被忽略的模块不会注册，依赖它的模块需自行处理其缺失的情况。
+    pub fn when(mut self, condition: Condition) -> Self {
+        for (key, type_name) in condition.modules() {
+            self.dependencies.push(DependencyDefinition { key, type_name, required: false });
+        }
+
+        self.condition = Some(match self.condition.take() {
+            Some(current) => current.and(condition),
+            None => condition,
+        });
+        self
+    }
+
+    fn is_satisfied(&self, provider: &ModuleProvider) -> bool {
+        match &self.condition {
+            Some(condition) if !condition.matches(provider) => {
+                info!("Skip module [{}], condition is not met: {}", self.display_name(), condition);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// 添加配置预检
+    ///
+    /// 通过 [`ModuleProvider::register_all`] 批量注册时，预检将在执行任何工厂方法之前集中执行，
+    /// 存在失败的预检时不会执行任何模块的工厂方法，以便在连接外部资源前发现全部配置错误。
+    pub fn config_check<F>(mut self, check: F) -> Self
+        where F: Fn(&ModuleProvider) -> Result<(), BootstrapError> + 'static
+    {
+        self.config_checks.push(Box::new(check));
+        self
+    }
+
+    /// 执行全部配置预检，返回失败的预检
+    ///
+    /// 模块已被覆盖或仅依赖配置的注册条件不满足时跳过预检，依赖其他模块的条件此时无法判断，仍执行预检。
+    pub fn check_config(&self, provider: &ModuleProvider) -> Vec<BootstrapError> {
+        if provider.overrides.contains(&self.key) {
+            return vec![];
+        }
+
+        if let Some(condition) = &self.condition {
+            if condition.modules().is_empty() && !condition.matches(provider) {
+                return vec![];
+            }
+        }
+
+        self.config_checks.iter()
+            .filter_map(|check| check(provider).err())
+            .collect()
+    }
+
+    /// 设置工厂方法的重试策略
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = DefinitionRetry::Policy(policy);
+        self
+    }
+
+    /// 注册时从 `Config` 模块的 `key` 配置节读取重试策略，配置节不存在时不重试
+    ///
+    /// 详见 [`ModuleProvider::retry_policy`]。
+    pub fn retry_config(mut self, key: &str) -> Self {
+        self.retry = DefinitionRetry::Config(key.to_string());
+        self
+    }
+
+    fn retry_policy(&self, provider: &ModuleProvider) -> Result<RetryPolicy, BootstrapError> {
+        match &self.retry {
+            DefinitionRetry::Policy(policy) => Ok(policy.clone()),
+            DefinitionRetry::Config(key) => provider.retry_policy(key),
+        }
+    }
+
+    /// 模块类型名称
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// 模块名称，仅具名模块存在
+    pub fn name(&self) -> Option<&str> {
+        self.key.name.as_deref()
+    }
+
+    fn display_name(&self) -> String {
+        display_name(self.type_name, self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert(1u8);
+        module_provider.insert(2u16);
+        module_provider.insert(4u32);
+        module_provider.insert(8u64);
+
+        assert!(module_provider.contains::<u8>());
+        assert!(module_provider.contains::<u16>());
+        assert!(module_provider.contains::<u32>());
+        assert!(module_provider.contains::<u64>());
+
+        assert_eq!(1, module_provider.get::<u8>().unwrap());
+        assert_eq!(2, module_provider.get::<u16>().unwrap());
+        assert_eq!(4, module_provider.get::<u32>().unwrap());
+        assert_eq!(8, module_provider.get::<u64>().unwrap());
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert(1u8);
+        module_provider.insert(2u16);
+        module_provider.insert(4u32);
+
+        assert!(module_provider.contains::<u8>());
+        assert!(module_provider.contains::<u16>());
+        assert!(module_provider.contains::<u32>());
+
+        module_provider.clear();
+
+        assert!(!module_provider.contains::<u8>());
+        assert!(!module_provider.contains::<u16>());
+        assert!(!module_provider.contains::<u32>());
+    }
+
+    #[tokio::test]
     async fn test_factory() {
         let mut module_provider = ModuleProvider::new();
 
@@ -256,4 +1542,347 @@ mod tests {
         assert_eq!(1, module_provider.get::<u8>().unwrap());
         assert_eq!(2, module_provider.get::<u16>().unwrap());
     }
+
+    async fn register_u8(_: &ModuleProvider) -> std::io::Result<u8> {
+        Ok(1)
+    }
+
+    async fn register_u16(ctx: &ModuleProvider) -> std::io::Result<u16> {
+        Ok(ctx.get::<u8>().unwrap() as u16 * 2)
+    }
+
+    async fn register_u32(ctx: &ModuleProvider) -> std::io::Result<u32> {
+        Ok(ctx.get::<u16>().unwrap() as u32 * 2)
+    }
+
+    #[tokio::test]
+    async fn test_register_all() {
+        let mut module_provider = ModuleProvider::new();
+
+        module_provider.register_all(vec![
+            ModuleDefinition::new(register_u32).depends_on::<u16>(),
+            ModuleDefinition::new(register_u16).depends_on::<u8>(),
+            ModuleDefinition::new(register_u8),
+        ]).await.unwrap();
+
+        assert_eq!(1, module_provider.get::<u8>().unwrap());
+        assert_eq!(2, module_provider.get::<u16>().unwrap());
+        assert_eq!(4, module_provider.get::<u32>().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_register_all_with_registered_dependency() {
+        let mut module_provider = ModuleProvider::initialize(1u8);
+
+        module_provider.register_all(vec![
+            ModuleDefinition::new(register_u16).depends_on::<u8>(),
+            ModuleDefinition::new(register_u32).depends_on::<u16>().after::<u64>(),
+        ]).await.unwrap();
+
+        assert_eq!(4, module_provider.get::<u32>().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_register_all_concurrent() {
+        use futures::lock::Mutex;
+
+        let events = Arc::new(Mutex::new(vec![]));
+
+        fn slow<T>(events: &Arc<Mutex<Vec<String>>>, obj: T) -> impl Fn(&ModuleProvider) -> LocalBoxFuture<'static, std::io::Result<T>> + Clone
+            where T: Send + Sync + Clone + 'static
+        {
+            let events = events.clone();
+            move |_| {
+                let (events, obj) = (events.clone(), obj.clone());
+                Box::pin(async move {
+                    events.lock().await.push(format!("{} start", type_name::<T>()));
+                    tokio::task::yield_now().await;
+                    events.lock().await.push(format!("{} end", type_name::<T>()));
+                    Ok(obj)
+                })
+            }
+        }
+
+        let mut module_provider = ModuleProvider::new();
+        module_provider.register_all_concurrent(vec![
+            ModuleDefinition::new(register_u32).depends_on::<u16>(),
+            ModuleDefinition::new(slow(&events, 2u16)),
+            ModuleDefinition::new(slow(&events, 1u8)),
+        ]).await.unwrap();
+
+        assert_eq!(vec!["u16 start", "u8 start", "u16 end", "u8 end"], *events.lock().await);
+        assert_eq!(4, module_provider.get::<u32>().unwrap());
+        assert_eq!(vec!["u16".to_string()], module_provider.infos[2].dependencies);
+    }
+
+    #[tokio::test]
+    async fn test_register_all_concurrent_failures() {
+        async fn failed_u8(_: &ModuleProvider) -> std::io::Result<u8> {
+            Err(std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "connection refused"))
+        }
+
+        async fn failed_u64(_: &ModuleProvider) -> std::io::Result<u64> {
+            Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out"))
+        }
+
+        let mut module_provider = ModuleProvider::new();
+        let err = module_provider.register_all_concurrent(vec![
+            ModuleDefinition::new(failed_u8),
+            ModuleDefinition::new(register_u32).depends_on::<u16>(),
+            ModuleDefinition::new(|_: &ModuleProvider| async { Ok::<_, std::io::Error>(2u16) }),
+            ModuleDefinition::named("replica", failed_u64),
+        ]).await.unwrap_err();
+
+        assert_eq!(
+            "2 module(s) failed to initialize: Module [u8] factory failed: connection refused; Module [u64(replica)] factory failed: timed out",
+            err.to_string()
+        );
+        assert!(module_provider.contains::<u16>());
+        assert!(!module_provider.contains::<u32>());
+    }
+
+    #[tokio::test]
+    async fn test_register_with_retry() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        static ATTEMPTS: AtomicU32 = AtomicU32::new(0);
+
+        async fn flaky(_: &ModuleProvider) -> std::io::Result<u8> {
+            match ATTEMPTS.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "connection refused")),
+                _ => Ok(1),
+            }
+        }
+
+        async fn unreachable(_: &ModuleProvider) -> std::io::Result<u16> {
+            Err(std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "connection refused"))
+        }
+
+        async fn missing_config(ctx: &ModuleProvider) -> Result<u32, BootstrapError> {
+            ctx.config_section::<u32, u32>("missing")
+        }
+
+        let policy = RetryPolicy::new()
+            .max_attempts(3)
+            .backoff(Duration::from_millis(1), Duration::from_millis(10));
+
+        let mut module_provider = ModuleProvider::new();
+        module_provider.register_with_retry(flaky, policy.clone()).await.unwrap();
+        assert_eq!(1, module_provider.get::<u8>().unwrap());
+        assert_eq!(3, ATTEMPTS.load(Ordering::SeqCst));
+
+        let err = module_provider.register_all(vec![
+            ModuleDefinition::new(unreachable).retry(policy.clone().max_attempts(100).timeout(Duration::from_millis(50))),
+        ]).await.unwrap_err();
+        assert!(matches!(err, BootstrapError::Timeout { .. }));
+        assert_eq!("Module [u16] factory timed out after 50ms", err.to_string());
+
+        let err = module_provider.register_with_retry(missing_config, policy.max_attempts(100)).await.unwrap_err();
+        assert!(matches!(err, BootstrapError::ConfigMissing { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_conditional_registration() {
+        let mut module_provider = ModuleProvider::initialize(crate::config::Profile::new("test"));
+
+        module_provider.register_if(Condition::profile("prod"), register_u8).await.unwrap();
+        assert!(!module_provider.contains::<u8>());
+
+        module_provider.register_all(vec![
+            ModuleDefinition::new(|_: &ModuleProvider| async { Ok::<_, std::io::Error>(8u64) })
+                .when(Condition::module_missing::<u32>()),
+            ModuleDefinition::new(register_u32).depends_on::<u16>(),
+            ModuleDefinition::new(|_: &ModuleProvider| async { Ok::<_, std::io::Error>(2u16) })
+                .when(Condition::profiles(&["dev", "test"])),
+        ]).await.unwrap();
+
+        assert_eq!(4, module_provider.get::<u32>().unwrap());
+        assert!(!module_provider.contains::<u64>());
+
+        module_provider.register_all_concurrent(vec![
+            ModuleDefinition::new(register_u8).when(Condition::module_present::<u32>()).when(Condition::profile("prod")),
+        ]).await.unwrap();
+        assert!(!module_provider.contains::<u8>());
+    }
+
+    #[tokio::test]
+    async fn test_register_all_missing_dependency() {
+        let mut module_provider = ModuleProvider::new();
+
+        let err = module_provider.register_all(vec![
+            ModuleDefinition::new(register_u32).depends_on::<u16>(),
+            ModuleDefinition::new(register_u16).depends_on::<u8>(),
+        ]).await.unwrap_err();
+
+        assert_eq!(
+            "Module [u16] depends on [u8], but it is not registered",
+            err.to_string()
+        );
+        assert!(!module_provider.contains::<u32>());
+    }
+
+    #[tokio::test]
+    async fn test_register_all_circular_dependency() {
+        let mut module_provider = ModuleProvider::new();
+
+        let err = module_provider.register_all(vec![
+            ModuleDefinition::new(register_u8).depends_on::<u32>(),
+            ModuleDefinition::new(register_u16).depends_on::<u8>(),
+            ModuleDefinition::new(register_u32).depends_on::<u16>(),
+        ]).await.unwrap_err();
+
+        assert_eq!(
+            "Circular module dependency: u8 -> u32 -> u16 -> u8",
+            err.to_string()
+        );
+        assert!(!module_provider.contains::<u8>());
+    }
+
+    #[tokio::test]
+    async fn test_named_modules() {
+        async fn register_replica(ctx: &ModuleProvider) -> std::io::Result<String> {
+            Ok(format!("{}-replica", ctx.get_ref::<String>().unwrap()))
+        }
+
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert_named("analytics", "analytics".to_string());
+        module_provider.register_all(vec![
+            ModuleDefinition::named("replica", register_replica).depends_on::<String>(),
+            ModuleDefinition::new(|_: &ModuleProvider| async { Ok::<_, std::io::Error>("primary".to_string()) }),
+        ]).await.unwrap();
+
+        assert_eq!("primary", module_provider.get::<String>().unwrap());
+        assert_eq!("primary-replica", module_provider.get_named::<String>("replica").unwrap());
+        assert!(module_provider.contains_named::<String>("analytics"));
+        assert!(!module_provider.contains_named::<String>("primary"));
+
+        module_provider.register_all(vec![
+            ModuleDefinition::named("replica", |ctx: &ModuleProvider| {
+                let len = ctx.get_named::<String>("archive").map(|archive| archive.len()).unwrap_or_default();
+                async move { Ok::<_, std::io::Error>(len) }
+            })
+                .after_named::<String>("archive")
+                .after_named::<String>("backup"),
+            ModuleDefinition::named("archive", |_: &ModuleProvider| async { Ok::<_, std::io::Error>("archive".to_string()) }),
+        ]).await.unwrap();
+        assert_eq!(7, module_provider.get_named::<usize>("replica").unwrap());
+
+        let err = module_provider.register_all(vec![
+            ModuleDefinition::new(register_u8).depends_on_named::<String>("backup"),
+        ]).await.unwrap_err();
+        assert_eq!(
+            "Module [u8] depends on [alloc::string::String(backup)], but it is not registered",
+            err.to_string()
+        );
+
+        let container = module_provider.into_module_container();
+        assert_eq!("analytics", container.get_named::<String>("analytics").unwrap());
+        assert_eq!(None, container.get_named::<u8>("analytics"));
+    }
+
+    #[tokio::test]
+    async fn test_override() {
+        async fn unreachable_u16(_: &ModuleProvider) -> std::io::Result<u16> {
+            unreachable!()
+        }
+
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert_with_hooks(1u8, ModuleHooks::new().readiness(|_| async { Ok(()) }));
+        module_provider.insert(1u32);
+        module_provider.override_with(8u8);
+        module_provider.override_with(16u16);
+        module_provider.override_named_with("replica", 32u32);
+
+        module_provider.insert(2u8);
+        module_provider.register(unreachable_u16).await.unwrap();
+        module_provider.register_named("replica", |_: &ModuleProvider| async { Ok::<_, std::io::Error>(2u32) }).await.unwrap();
+
+        assert_eq!(8, module_provider.get::<u8>().unwrap());
+        assert_eq!(16, module_provider.get::<u16>().unwrap());
+        assert_eq!(1, module_provider.get::<u32>().unwrap());
+        assert_eq!(32, module_provider.get_named::<u32>("replica").unwrap());
+
+        let container = module_provider.into_module_container();
+        let names = container.registers().iter().map(|register| register.module_name()).collect::<Vec<_>>();
+        assert_eq!(vec!["u8", "u32", "u16", "u32"], names);
+        assert!(container.registers()[0].readiness_probe().is_none());
+
+        let overridden = container.with_named_override("replica", 64u32);
+        assert_eq!(64, overridden.get_named::<u32>("replica").unwrap());
+        assert_eq!(32, container.get_named::<u32>("replica").unwrap());
+        assert_eq!(4, overridden.registers().len());
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_hooks() {
+        use std::sync::Mutex;
+
+        fn hooks<T>(events: &Arc<Mutex<Vec<String>>>, name: &'static str) -> ModuleHooks<T>
+            where T: Send + Sync + Clone + 'static
+        {
+            let (start, ready, shutdown) = (events.clone(), events.clone(), events.clone());
+            ModuleHooks::new()
+                .on_start(move |_| {
+                    start.lock().unwrap().push(format!("start {}", name));
+                    async { Ok(()) }
+                })
+                .on_ready(move |_| {
+                    ready.lock().unwrap().push(format!("ready {}", name));
+                    async { Ok(()) }
+                })
+                .on_shutdown(move |_| {
+                    shutdown.lock().unwrap().push(format!("shutdown {}", name));
+                    async { Err(anyhow::anyhow!("shutdown failed")) }
+                })
+        }
+
+        let events = Arc::new(Mutex::new(vec![]));
+        let mut module_provider = ModuleProvider::new();
+        module_provider.register_all(vec![
+            ModuleDefinition::with_hooks(register_u16, hooks(&events, "u16")).depends_on::<u8>(),
+            ModuleDefinition::with_hooks(register_u8, hooks(&events, "u8")),
+        ]).await.unwrap();
+        module_provider.insert(1u64);
+
+        let container = module_provider.into_module_container();
+        container.start().await.unwrap();
+        container.ready().await.unwrap();
+        container.shutdown().await;
+
+        assert_eq!(
+            vec!["start u8", "start u16", "ready u8", "ready u16", "shutdown u16", "shutdown u8"],
+            *events.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_failure_shutdown_started_modules() {
+        use std::sync::Mutex;
+
+        fn hooks<T>(events: &Arc<Mutex<Vec<String>>>, name: &'static str) -> ModuleHooks<T>
+            where T: Send + Sync + Clone + 'static
+        {
+            let events = events.clone();
+            ModuleHooks::new().on_shutdown(move |_| {
+                events.lock().unwrap().push(format!("shutdown {}", name));
+                async { Ok(()) }
+            })
+        }
+
+        let events = Arc::new(Mutex::new(vec![]));
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert_with_hooks(1u8, hooks(&events, "u8"));
+        module_provider.insert_named_with_hooks("replica", 2u8, hooks(&events, "replica"));
+        module_provider.insert_with_hooks(
+            3u16,
+            hooks(&events, "u16").on_start(|_| async { Err(anyhow::anyhow!("connection refused")) }),
+        );
+        module_provider.insert_with_hooks(4u32, hooks(&events, "u32"));
+
+        let container = module_provider.into_module_container();
+        let err = container.start().await.unwrap_err();
+
+        assert_eq!("Module [u16] failed to start", err.to_string());
+        assert_eq!(vec!["shutdown replica", "shutdown u8"], *events.lock().unwrap());
+    }
 }
\ No newline at end of file
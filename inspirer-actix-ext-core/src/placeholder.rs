@@ -0,0 +1,207 @@
+//! 配置值中的占位符
+//!
+//! [`config_provider`](crate::config::config_provider) 在合并全部配置来源后，解析字符串配置值中的占位符：
+//!
+//! ```toml
+//! [database]
+//! host = "${DB_HOST}"
+//! port = "${DB_PORT:-3306}"
+//! password = "${file:/run/secrets/db_password}"
+//! ```
+//!
+//! - `${VAR}`：环境变量 `VAR` 的值，未设置时报错；
+//! - `${VAR:-default}`：环境变量 `VAR` 未设置或为空时使用 `default`；
+//! - `${file:/path}`：文件内容并去除末尾换行，适用于 Docker、Kubernetes 的 secrets。
+//!
+//! 需要字面量 `${` 时写作 `$${`。不在占位符中的 `file:` 保持原样，例如 SQLite 的 `file:data.db` 连接串。
+//! 占位符无法解析时返回的错误中包含对应的配置键。
+
+use std::collections::HashMap;
+
+use config::{Config, ConfigError, Value};
+
+/// 解析配置中全部字符串值的占位符
+pub fn resolve(config: &mut Config) -> Result<(), ConfigError> {
+    let mut resolved = vec![];
+    collect(config.cache.clone(), String::new(), &mut resolved)?;
+
+    for (key, value) in resolved {
+        debug!("Resolve placeholder of config [{}].", key);
+        config.set(&key, value)?;
+    }
+
+    Ok(())
+}
+
+/// 收集需要替换的配置值，表按键逐项替换，数组则整体替换
+fn collect(value: Value, key: String, resolved: &mut Vec<(String, Value)>) -> Result<(), ConfigError> {
+    if let Ok(table) = value.clone().into_table() {
+        for (name, value) in table {
+            let key = if key.is_empty() { name } else { format!("{}.{}", key, name) };
+            collect(value, key, resolved)?;
+        }
+    } else if let (value, true) = rebuild(value, &key)? {
+        resolved.push((key, value));
+    }
+
+    Ok(())
+}
+
+/// 重建配置值，返回重建后的值及其是否包含占位符
+fn rebuild(value: Value, key: &str) -> Result<(Value, bool), ConfigError> {
+    if let Ok(table) = value.clone().into_table() {
+        let mut changed = false;
+        let mut rebuilt = HashMap::with_capacity(table.len());
+        for (name, value) in table {
+            let (value, resolved) = rebuild(value, &format!("{}.{}", key, name))?;
+            changed |= resolved;
+            rebuilt.insert(name, value);
+        }
+
+        Ok((Value::from(rebuilt), changed))
+    } else if let Ok(array) = value.clone().into_array() {
+        let mut changed = false;
+        let mut rebuilt = Vec::with_capacity(array.len());
+        for (index, value) in array.into_iter().enumerate() {
+            let (value, resolved) = rebuild(value, &format!("{}[{}]", key, index))?;
+            changed |= resolved;
+            rebuilt.push(value);
+        }
+
+        Ok((Value::from(rebuilt), changed))
+    } else {
+        match value.clone().into_str().ok().map(|raw| resolve_value(key, &raw)).transpose()? {
+            Some(Some(resolved)) => Ok((Value::from(resolved), true)),
+            _ => Ok((value, false)),
+        }
+    }
+}
+
+fn unresolved(key: &str, reason: String) -> ConfigError {
+    ConfigError::Message(format!("Cannot resolve config [{}]: {}", key, reason))
+}
+
+/// 解析单个配置值，不包含占位符时返回 `None`
+fn resolve_value(key: &str, raw: &str) -> Result<Option<String>, ConfigError> {
+    if !raw.contains('$') {
+        return Ok(None);
+    }
+
+    let mut output = String::with_capacity(raw.len());
+    let mut rest = raw;
+
+    while let Some(start) = rest.find('$') {
+        output.push_str(&rest[..start]);
+        let tail = &rest[start..];
+
+        if let Some(tail) = tail.strip_prefix("$${") {
+            output.push_str("${");
+            rest = tail;
+            continue;
+        }
+
+        let placeholder = match tail.strip_prefix("${") {
+            Some(placeholder) => placeholder,
+            None => {
+                output.push('$');
+                rest = &tail[1..];
+                continue;
+            }
+        };
+
+        let end = placeholder.find('}')
+            .ok_or_else(|| unresolved(key, format!("unclosed placeholder in `{}`", raw)))?;
+
+        if let Some(path) = placeholder[..end].strip_prefix("file:") {
+            let content = std::fs::read_to_string(path)
+                .map_err(|err| unresolved(key, format!("cannot read file [{}], {}", path, err)))?;
+            output.push_str(content.trim_end_matches(&['\r', '\n'][..]));
+            rest = &placeholder[end + 1..];
+            continue;
+        }
+
+        let (name, default) = match placeholder[..end].find(":-") {
+            Some(index) => (&placeholder[..index], Some(&placeholder[index + 2..end])),
+            None => (&placeholder[..end], None),
+        };
+
+        if name.is_empty() {
+            return Err(unresolved(key, format!("empty placeholder in `{}`", raw)));
+        }
+
+        let value = match (std::env::var(name), default) {
+            (Ok(value), Some(default)) if value.is_empty() => default.to_string(),
+            (Ok(value), _) => value,
+            (Err(_), Some(default)) => default.to_string(),
+            (Err(_), None) => return Err(unresolved(key, format!("environment variable [{}] is not set", name))),
+        };
+
+        output.push_str(&value);
+        rest = &placeholder[end + 1..];
+    }
+
+    output.push_str(rest);
+    Ok(Some(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let secret = crate::tests::temp_dir("placeholder").join("secret");
+        std::fs::write(&secret, "s3cr3t\n").unwrap();
+
+        std::env::set_var("PLACEHOLDER_TEST_HOST", "db.internal");
+        std::env::set_var("PLACEHOLDER_TEST_EMPTY", "");
+        std::env::remove_var("PLACEHOLDER_TEST_PORT");
+
+        let mut config = Config::new();
+        config.set("database.host", "${PLACEHOLDER_TEST_HOST}").unwrap();
+        config.set("database.port", "${PLACEHOLDER_TEST_PORT:-3306}").unwrap();
+        config.set("database.username", "${PLACEHOLDER_TEST_EMPTY:-root}").unwrap();
+        config.set("database.password", format!("${{file:{}}}", secret.display())).unwrap();
+        config.set("database.url", "mysql://${PLACEHOLDER_TEST_HOST}:$${PORT}/$1").unwrap();
+        config.set("database.dsn", "file::memory:?cache=shared").unwrap();
+        config.set("cache.path", "file:cache.db").unwrap();
+        config.set("hosts", vec!["${PLACEHOLDER_TEST_HOST}", "localhost"]).unwrap();
+        config.set("replica", 2).unwrap();
+
+        resolve(&mut config).unwrap();
+
+        assert_eq!("db.internal", config.get_str("database.host").unwrap());
+        assert_eq!(3306, config.get::<u16>("database.port").unwrap());
+        assert_eq!("root", config.get_str("database.username").unwrap());
+        assert_eq!("s3cr3t", config.get_str("database.password").unwrap());
+        assert_eq!("mysql://db.internal:${PORT}/$1", config.get_str("database.url").unwrap());
+        assert_eq!("file::memory:?cache=shared", config.get_str("database.dsn").unwrap());
+        assert_eq!("file:cache.db", config.get_str("cache.path").unwrap());
+        assert_eq!(vec!["db.internal".to_string(), "localhost".into()], config.get::<Vec<String>>("hosts").unwrap());
+        assert_eq!(2, config.get_int("replica").unwrap());
+    }
+
+    #[test]
+    fn test_unresolved() {
+        std::env::remove_var("PLACEHOLDER_TEST_MISSING");
+
+        let mut config = Config::new();
+        config.set("mail.password", "${PLACEHOLDER_TEST_MISSING}").unwrap();
+        assert_eq!(
+            "Cannot resolve config [mail.password]: environment variable [PLACEHOLDER_TEST_MISSING] is not set",
+            resolve(&mut config).unwrap_err().to_string()
+        );
+
+        let mut config = Config::new();
+        config.set("mail.host", "${MAIL_HOST").unwrap();
+        assert_eq!(
+            "Cannot resolve config [mail.host]: unclosed placeholder in `${MAIL_HOST`",
+            resolve(&mut config).unwrap_err().to_string()
+        );
+
+        let mut config = Config::new();
+        config.set("mail.password", "${file:/nonexistent/inspirer/secret}").unwrap();
+        assert!(resolve(&mut config).unwrap_err().to_string()
+            .starts_with("Cannot resolve config [mail.password]: cannot read file [/nonexistent/inspirer/secret]"));
+    }
+}
@@ -0,0 +1,206 @@
+//! 可热替换的模块
+//!
+//! 通过 [`ModuleProvider::insert_reloadable`] 或 [`ModuleProvider::register_reloadable`] 注册的模块，
+//! 可以在服务运行期间通过 [`ModuleContainer::reload`] 原子地替换为新的实例，例如在配置变更后重建 Redis 客户端：
+//!
+//! ```ignore
+//! module_provider.register_reloadable(register_redis_client).await?;
+//! let container = module_provider.into_module_container();
+//!
+//! // 配置变更后
+//! container.reload(register_redis_client).await?;
+//! ```
+//!
+//! 服务可以像普通模块一样直接依赖 `T`，每个请求在首次解析时获取当时的最新实例，并在该请求内保持不变；
+//! 也可以依赖 [`Reloadable<T>`] 句柄，在需要时通过 [`Reloadable::get`] 获取最新实例。
+//! 其他模块的工厂方法可以直接通过 `ctx.get::<T>()` 获取该模块：启动时为初始实例，在 [`ModuleContainer::reload`]
+//! 执行的工厂方法中为当前实例；需要持有句柄时则获取 `Reloadable<T>`。
+//!
+//! 替换后已经获取到旧实例的请求不受影响，旧实例将在不再被使用后释放。
+
+use std::any::type_name;
+use std::fmt;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crate::error::BootstrapError;
+use crate::module::{display_name, ModuleContainer, ModuleFactoryFn, ModuleKey, ModuleProvider};
+
+/// 可热替换的模块句柄
+pub struct Reloadable<T> {
+    inner: Arc<RwLock<Arc<T>>>,
+}
+
+impl<T> Clone for Reloadable<T> {
+    fn clone(&self) -> Self {
+        Reloadable { inner: self.inner.clone() }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Reloadable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Reloadable").field(&self.get()).finish()
+    }
+}
+
+impl<T> Reloadable<T> {
+    pub fn new(module: T) -> Self {
+        Reloadable { inner: Arc::new(RwLock::new(Arc::new(module))) }
+    }
+
+    /// 获取当前实例
+    pub fn get(&self) -> Arc<T> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// 替换为新的实例，返回被替换的实例
+    pub fn swap(&self, module: T) -> Arc<T> {
+        let mut current = self.inner.write().unwrap_or_else(PoisonError::into_inner);
+        std::mem::replace(&mut *current, Arc::new(module))
+    }
+}
+
+impl ModuleContainer {
+    /// 获取可热替换模块的句柄
+    pub fn reloadable<T>(&self) -> Option<Reloadable<T>>
+        where T: Send + Sync + 'static
+    {
+        self.module::<Reloadable<T>>(&ModuleKey::of::<Reloadable<T>>())
+    }
+
+    /// 通过工厂方法重建可热替换的模块，并替换当前实例
+    ///
+    /// 工厂方法接收的模块注册器为当前模块容器的快照。工厂方法失败时保留当前实例；
+    /// 模块未以可热替换的方式注册时返回 [`BootstrapError::NotReloadable`]，模块已被覆盖时不执行工厂方法。
+    pub async fn reload<T, F, E>(&self, factory: F) -> Result<(), BootstrapError>
+        where
+                for<'a> F: ModuleFactoryFn<'a, T, E>,
+                T: Send + Sync + Clone + 'static,
+                E: std::error::Error + Send + Sync + 'static,
+    {
+        let module = display_name(type_name::<T>(), None);
+        let reloadable = self.reloadable::<T>()
+            .ok_or_else(|| BootstrapError::NotReloadable { module: module.clone() })?;
+
+        if self.modules().iter().any(|info| info.is(type_name::<T>(), None) && info.overridden) {
+            info!("Module [{}] is overridden, skip reload.", module);
+            return Ok(());
+        }
+
+        let provider = ModuleProvider::snapshot(self);
+        let obj = factory.call(&provider).await
+            .map_err(|err| BootstrapError::from_factory(module.clone(), err))?;
+
+        info!("Reload module [{}]", module);
+        reloadable.swap(obj);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, test, web};
+
+    use crate::service::{IntoService, Service};
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct FeatureFlags(Vec<&'static str>);
+
+    struct FlagService(FeatureFlags, Reloadable<FeatureFlags>);
+
+    impl IntoService<(FeatureFlags, Reloadable<FeatureFlags>)> for FlagService {
+        fn init(deps: (FeatureFlags, Reloadable<FeatureFlags>)) -> Self {
+            FlagService(deps.0, deps.1)
+        }
+    }
+
+    async fn load_flags(ctx: &ModuleProvider) -> std::io::Result<FeatureFlags> {
+        Ok(FeatureFlags(ctx.get::<Vec<&'static str>>().unwrap_or_default()))
+    }
+
+    #[actix_rt::test]
+    async fn test_reload() {
+        let mut module_provider = ModuleProvider::new();
+        let flags = module_provider.insert_reloadable(FeatureFlags(vec!["legacy"]));
+        module_provider.insert(vec!["dark-mode", "beta"]);
+
+        let container = module_provider.into_module_container();
+        let mut app = test::init_service(
+            App::new()
+                .configure(container.module_provider())
+                .route("/flags", web::get().to(|srv: Service| async move {
+                    let flag_service = srv.get::<_, FlagService>()?;
+                    assert_eq!((flag_service.0).0, flag_service.1.get().0);
+                    Ok::<_, crate::error::Error>((flag_service.0).0.join(","))
+                }))
+        ).await;
+
+        let call = || test::TestRequest::get().uri("/flags").to_request();
+        assert_eq!("legacy", test::read_body(test::call_service(&mut app, call()).await).await);
+
+        container.reload(load_flags).await.unwrap();
+        assert_eq!(vec!["dark-mode", "beta"], flags.get().0);
+        assert_eq!("dark-mode,beta", test::read_body(test::call_service(&mut app, call()).await).await);
+        assert_eq!(vec!["dark-mode", "beta"], container.get::<FeatureFlags>().unwrap().0);
+
+        container.reload(|ctx: &ModuleProvider| {
+            let mut flags = ctx.get::<FeatureFlags>().unwrap();
+            flags.0.push("canary");
+            async move { Ok::<_, std::io::Error>(flags) }
+        }).await.unwrap();
+        assert_eq!(vec!["dark-mode", "beta", "canary"], flags.get().0);
+
+        let err = container.reload(|_: &ModuleProvider| async { Ok::<_, std::io::Error>(1u8) }).await.unwrap_err();
+        assert_eq!("Module [u8] is not registered as reloadable", err.to_string());
+    }
+
+    async fn unreachable_name(_: &ModuleProvider) -> std::io::Result<String> {
+        unreachable!()
+    }
+
+    #[actix_rt::test]
+    async fn test_reload_override() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.override_with(FeatureFlags(vec!["fake"]));
+        module_provider.override_with("fake".to_string());
+        module_provider.insert(vec!["dark-mode"]);
+
+        let flags = module_provider.insert_reloadable(FeatureFlags(vec!["real"]));
+        let name = module_provider.register_reloadable(unreachable_name).await.unwrap();
+        let version = module_provider.register_reloadable(|_: &ModuleProvider| async { Ok::<_, std::io::Error>(1u32) }).await.unwrap();
+
+        assert_eq!(vec!["fake"], flags.get().0);
+        assert_eq!("fake", name.get().as_str());
+        assert_eq!(vec!["fake"], module_provider.get::<FeatureFlags>().unwrap().0);
+        assert_eq!("fake", module_provider.get::<String>().unwrap());
+
+        let container = module_provider.into_module_container();
+        let mut app = test::init_service(
+            App::new()
+                .configure(container.module_provider())
+                .route("/flags", web::get().to(|srv: Service| async move {
+                    let flag_service = srv.get::<_, FlagService>()?;
+                    Ok::<_, crate::error::Error>((flag_service.0).0.join(","))
+                }))
+        ).await;
+
+        container.reload(load_flags).await.unwrap();
+        assert_eq!(vec!["fake"], container.get::<FeatureFlags>().unwrap().0);
+        let req = test::TestRequest::get().uri("/flags").to_request();
+        assert_eq!("fake", test::read_body(test::call_service(&mut app, req).await).await);
+
+        let infos = container.modules();
+        assert!(infos.iter().all(|info| !info.module.contains("Reloadable")));
+        assert!(infos.iter().find(|info| info.is(type_name::<FeatureFlags>(), None)).unwrap().overridden);
+        let info = infos.iter().find(|info| info.is("u32", None)).unwrap();
+        assert!(!info.overridden && info.factory_duration.is_some());
+
+        let overridden = container.with_override(2u32);
+        overridden.reload(|_: &ModuleProvider| async { Ok::<_, std::io::Error>(3u32) }).await.unwrap();
+        assert_eq!(Some(2), overridden.get::<u32>());
+        assert_eq!(Some(1), container.get::<u32>());
+        assert_eq!(1, *version.get());
+    }
+}
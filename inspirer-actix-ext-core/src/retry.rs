@@ -0,0 +1,193 @@
+//! 模块工厂方法的重试策略
+//!
+//! 应用与其依赖的服务（数据库、Redis 等）同时启动时，依赖的服务往往需要数秒才能就绪。
+//! 通过重试策略，注册器将在工厂方法失败后按指数退避重新执行，并可限制模块初始化的总耗时：
+//!
+//! ```
+//! use std::time::Duration;
+//! use inspirer_actix_ext_core::retry::RetryPolicy;
+//!
+//! let policy = RetryPolicy::new()
+//!     .max_attempts(5)
+//!     .backoff(Duration::from_millis(200), Duration::from_secs(5))
+//!     .timeout(Duration::from_secs(30));
+//! ```
+//!
+//! 重试策略也可以从 `Config` 模块中读取，参见 [`ModuleProvider::retry_policy`]，例如：
+//!
+//! ```toml
+//! [database.retry]
+//! max_attempts = 5
+//! initial_backoff_ms = 200
+//! max_backoff_ms = 5000
+//! multiplier = 2.0
+//! jitter = 0.2
+//! timeout_ms = 30000
+//! ```
+//!
+//! 仅工厂方法本身的错误会触发重试，配置缺失、依赖缺失等错误将直接返回。
+
+use std::time::Duration;
+
+use config::{Config, ConfigError};
+use rand::Rng;
+use serde::{Deserialize, Deserializer};
+
+use crate::error::BootstrapError;
+use crate::module::ModuleProvider;
+
+/// 重试策略
+///
+/// 默认仅执行一次且不限制耗时，即不进行重试。
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub(crate) max_attempts: u32,
+    #[serde(rename = "initial_backoff_ms", deserialize_with = "millis")]
+    pub(crate) initial_backoff: Duration,
+    #[serde(rename = "max_backoff_ms", deserialize_with = "millis")]
+    pub(crate) max_backoff: Duration,
+    pub(crate) multiplier: f64,
+    pub(crate) jitter: f64,
+    #[serde(rename = "timeout_ms", deserialize_with = "optional_millis")]
+    pub(crate) timeout: Option<Duration>,
+}
+
+fn millis<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_millis)
+}
+
+fn optional_millis<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+    Option::<u64>::deserialize(deserializer).map(|millis| millis.map(Duration::from_millis))
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.2,
+            timeout: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 最大执行次数（包含首次执行）
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// 首次重试前的等待时间及等待时间上限
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// 每次重试后等待时间的增长倍数
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// 等待时间的随机抖动比例，取值 `0.0` 至 `1.0`，用于避免多个实例同时重试
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 模块初始化的总耗时上限，包含所有重试及等待时间
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 第 `attempt` 次执行失败后的等待时间
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let backoff = self.initial_backoff.as_secs_f64() * self.multiplier.max(1.0).powi(exponent);
+        let backoff = backoff.min(self.max_backoff.as_secs_f64());
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = if jitter > 0.0 {
+            rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter)
+        } else {
+            1.0
+        };
+
+        Duration::from_secs_f64(backoff * factor)
+    }
+}
+
+impl ModuleProvider {
+    /// 从 `Config` 模块中读取 `key` 配置节作为重试策略
+    ///
+    /// 未注册 `Config` 模块或配置节不存在时返回默认策略（不重试）。
+    pub fn retry_policy(&self, key: &str) -> Result<RetryPolicy, BootstrapError> {
+        let config = match self.get_ref::<Config>() {
+            Some(config) => config,
+            None => return Ok(RetryPolicy::default()),
+        };
+
+        match config.get::<RetryPolicy>(key) {
+            Ok(policy) => Ok(policy),
+            Err(ConfigError::NotFound(_)) => Ok(RetryPolicy::default()),
+            Err(source) => Err(BootstrapError::ConfigInvalid {
+                module: "RetryPolicy".to_string(),
+                key: key.to_string(),
+                source: Box::new(source),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::new()
+            .max_attempts(5)
+            .backoff(Duration::from_millis(100), Duration::from_millis(300))
+            .jitter(0.0);
+
+        assert_eq!(Duration::from_millis(100), policy.backoff_for(1));
+        assert_eq!(Duration::from_millis(200), policy.backoff_for(2));
+        assert_eq!(Duration::from_millis(300), policy.backoff_for(3));
+
+        let policy = policy.jitter(0.5);
+        for _ in 0..100 {
+            let backoff = policy.backoff_for(2);
+            assert!(backoff >= Duration::from_millis(100) && backoff <= Duration::from_millis(300));
+        }
+    }
+
+    #[test]
+    fn test_retry_policy_from_config() {
+        let mut config = Config::new();
+        config.set("database.retry.max_attempts", 5).unwrap();
+        config.set("database.retry.initial_backoff_ms", 200).unwrap();
+        config.set("database.retry.timeout_ms", 30000).unwrap();
+        config.set("redis.retry.max_attempts", "many").unwrap();
+
+        let module_provider = ModuleProvider::initialize(config);
+        let policy = module_provider.retry_policy("database.retry").unwrap();
+
+        assert_eq!(5, policy.max_attempts);
+        assert_eq!(Duration::from_millis(200), policy.initial_backoff);
+        assert_eq!(Duration::from_secs(10), policy.max_backoff);
+        assert_eq!(Some(Duration::from_secs(30)), policy.timeout);
+
+        assert_eq!(RetryPolicy::default(), module_provider.retry_policy("mail.retry").unwrap());
+        assert!(module_provider.retry_policy("redis.retry").is_err());
+    }
+}
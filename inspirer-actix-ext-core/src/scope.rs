@@ -0,0 +1,341 @@
+//! 请求作用域模块
+//!
+//! 请求作用域模块由工厂方法基于当前请求创建，例如当前用户、请求 ID 等，
+//! 同一请求内多次解析将复用首次创建的结果（缓存于请求的 extensions 中），不同请求之间互不影响。
+//!
+//! ```ignore
+//! #[derive(Clone)]
+//! pub struct RequestId(String);
+//!
+//! module_provider.register_scoped(|req: &HttpRequest| {
+//!     req.headers()
+//!         .get("x-request-id")
+//!         .and_then(|value| value.to_str().ok())
+//!         .map(|value| RequestId(value.to_string()))
+//!         .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Missing request id"))
+//! });
+//! ```
+//!
+//! 注册后即可与应用级模块一样在 `IntoService` 的依赖中直接声明 `RequestId`。
+//!
+//! 需要异步创建或无法 `Clone` 的模块（例如数据库事务）通过 [`ModuleProvider::register_scoped_async`](crate::module::ModuleProvider::register_scoped_async)
+//! 注册，并以 [`Scoped<T>`] 句柄共享。异步工厂方法在处理函数提取 `Scoped<T>` 时执行，
+//! 此后同一请求内的服务即可在依赖中声明 `Scoped<T>`：
+//!
+//! ```ignore
+//! module_provider.register_scoped_async(|req: &HttpRequest| {
+//!     let pool = req.app_data::<Data<MySqlPool>>().cloned();
+//!     async move { pool.expect("pool is registered").begin().await }.boxed_local()
+//! });
+//!
+//! async fn create_order(tx: Scoped<Transaction<'static, MySql>>, srv: Service) -> Result<HttpResponse, Error> {
+//!     srv.get::<_, OrderService>()?.create().await?;
+//!     srv.get::<_, StockService>()?.reserve().await?;
+//!     tx.take().await.unwrap().commit().await?;
+//!     Ok(HttpResponse::Ok().finish())
+//! }
+//! ```
+
+use std::any::{Any, type_name};
+use std::sync::Arc;
+
+use actix_web::{FromRequest, HttpRequest};
+use actix_web::dev::Payload;
+use actix_web::web::{Data, ServiceConfig};
+use futures::future::{FutureExt, LocalBoxFuture};
+use futures::lock::{MappedMutexGuard, Mutex, MutexGuard};
+
+use crate::error::Error;
+use crate::module::ModuleRegister;
+
+type ScopedFn<T> = Box<dyn Fn(&HttpRequest) -> Result<T, Box<dyn std::error::Error + Send + Sync>> + Send + Sync>;
+
+/// 请求作用域模块工厂
+pub struct ScopedFactory<T>(ScopedFn<T>);
+
+impl<T> ScopedFactory<T>
+    where T: Clone + 'static
+{
+    pub(crate) fn new<F, E>(factory: F) -> Self
+        where F: Fn(&HttpRequest) -> Result<T, E> + Send + Sync + 'static,
+              E: std::error::Error + Send + Sync + 'static
+    {
+        ScopedFactory(Box::new(move |req| factory(req).map_err(|err| err.into())))
+    }
+
+    fn make(&self, req: &HttpRequest) -> Result<T, Error> {
+        (self.0)(req).map_err(|source| Error::DependencyInitFailed { module: type_name::<T>(), source })
+    }
+}
+
+/// 请求作用域内缓存的模块
+struct ScopedCache<T>(T);
+
+/// 解析请求作用域模块
+///
+/// 优先从请求缓存中获取，不存在时调用工厂方法创建并写入缓存。
+pub(crate) fn resolve_scoped<T>(req: &HttpRequest) -> Result<T, Error>
+    where T: Clone + 'static
+{
+    if let Some(cached) = req.extensions().get::<ScopedCache<T>>() {
+        return Ok(cached.0.clone());
+    }
+
+    let factory = req.app_data::<Data<ScopedFactory<T>>>()
+        .ok_or(Error::DependencyNotFound(type_name::<T>()))?;
+
+    let module = factory.make(req)?;
+    req.extensions_mut().insert(ScopedCache(module.clone()));
+
+    Ok(module)
+}
+
+type AsyncScopedFn<T> = Box<dyn Fn(&HttpRequest) -> LocalBoxFuture<'static, Result<T, Box<dyn std::error::Error + Send + Sync>>> + Send + Sync>;
+
+/// 异步请求作用域模块工厂
+pub struct AsyncScopedFactory<T>(AsyncScopedFn<T>);
+
+impl<T: 'static> AsyncScopedFactory<T> {
+    pub(crate) fn new<F, E>(factory: F) -> Self
+        where F: Fn(&HttpRequest) -> LocalBoxFuture<'static, Result<T, E>> + Send + Sync + 'static,
+              E: std::error::Error + Send + Sync + 'static
+    {
+        AsyncScopedFactory(Box::new(move |req| factory(req).map(|result| result.map_err(|err| err.into())).boxed_local()))
+    }
+}
+
+/// 异步请求作用域模块句柄
+///
+/// 同一请求内的各个句柄共享同一实例，通过 [`Scoped::lock`] 访问，通过 [`Scoped::take`] 取出实例，
+/// 例如提交事务。句柄在服务中作为依赖解析前，需要先在处理函数中提取或通过 [`Scoped::resolve`] 创建。
+pub struct Scoped<T>(Arc<Mutex<Option<T>>>);
+
+impl<T> Clone for Scoped<T> {
+    fn clone(&self) -> Self {
+        Scoped(self.0.clone())
+    }
+}
+
+impl<T: 'static> Scoped<T> {
+    /// 解析当前请求的实例，首次解析时执行异步工厂方法并缓存于请求中
+    pub async fn resolve(req: &HttpRequest) -> Result<Self, Error> {
+        if let Some(cached) = req.extensions().get::<ScopedCache<Scoped<T>>>() {
+            return Ok(cached.0.clone());
+        }
+
+        let factory = req.app_data::<Data<AsyncScopedFactory<T>>>()
+            .ok_or(Error::DependencyNotFound(type_name::<T>()))?
+            .clone();
+
+        let module = (factory.0)(req).await
+            .map_err(|source| Error::DependencyInitFailed { module: type_name::<T>(), source })?;
+
+        let mut extensions = req.extensions_mut();
+        let cached = extensions.get::<ScopedCache<Scoped<T>>>().map(|cached| cached.0.clone());
+        Ok(cached.unwrap_or_else(|| {
+            let scoped = Scoped(Arc::new(Mutex::new(Some(module))));
+            extensions.insert(ScopedCache(scoped.clone()));
+            scoped
+        }))
+    }
+
+    /// 锁定并访问实例，实例已被取出时返回 `None`
+    pub async fn lock(&self) -> Option<MappedMutexGuard<'_, Option<T>, T>> {
+        let guard = self.0.lock().await;
+        if guard.is_none() {
+            return None;
+        }
+
+        Some(MutexGuard::map(guard, |module| module.as_mut().unwrap()))
+    }
+
+    /// 取出实例，此后同一请求内的其他句柄将无法再访问
+    pub async fn take(&self) -> Option<T> {
+        self.0.lock().await.take()
+    }
+}
+
+impl<T: 'static> FromRequest for Scoped<T> {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Error>>;
+    type Config = ();
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        async move { Scoped::resolve(&req).await }.boxed_local()
+    }
+}
+
+/// 异步请求作用域模块注册器
+pub(crate) struct AsyncScopedModuleRegister<T>(pub Arc<AsyncScopedFactory<T>>);
+
+impl<T: 'static> ModuleRegister for AsyncScopedModuleRegister<T> {
+    fn register(&self, service: &mut ServiceConfig) {
+        service.app_data(Data::from(self.0.clone()));
+    }
+
+    fn get_module(&self) -> Box<dyn Any> {
+        Box::new(self.0.clone())
+    }
+
+    fn module_name(&self) -> &'static str {
+        type_name::<Scoped<T>>()
+    }
+}
+
+/// 请求作用域模块注册器
+pub(crate) struct ScopedModuleRegister<T>(pub Arc<ScopedFactory<T>>);
+
+impl<T> ModuleRegister for ScopedModuleRegister<T>
+    where T: Clone + 'static
+{
+    fn register(&self, service: &mut ServiceConfig) {
+        service.app_data(Data::from(self.0.clone()));
+    }
+
+    fn get_module(&self) -> Box<dyn Any> {
+        Box::new(self.0.clone())
+    }
+
+    fn module_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use actix_web::{App, test, web};
+    use actix_web::http::StatusCode;
+
+    use crate::module::ModuleProvider;
+    use crate::service::{IntoService, Service};
+
+    use super::*;
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    static BEGINS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone)]
+    struct RequestId(String);
+
+    struct AuditService(String, RequestId);
+
+    impl IntoService<(String, RequestId)> for AuditService {
+        fn init(deps: (String, RequestId)) -> Self {
+            AuditService(deps.0, deps.1)
+        }
+    }
+
+    async fn audit(srv: Service) -> Result<String, Error> {
+        let first = srv.get::<_, AuditService>()?;
+        let second = srv.get::<_, AuditService>()?;
+        assert_eq!(first.1.0, second.1.0);
+
+        Ok(format!("{} {}", first.0, first.1.0))
+    }
+
+    #[actix_rt::test]
+    async fn test_scoped_module() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert("audit".to_string());
+        module_provider.register_scoped(|req: &HttpRequest| {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            req.headers()
+                .get("x-request-id")
+                .and_then(|value| value.to_str().ok())
+                .map(|value| RequestId(value.to_string()))
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "missing request id"))
+        });
+
+        let container = module_provider.into_module_container();
+        let mut app = test::init_service(
+            App::new()
+                .configure(container.module_provider())
+                .route("/", web::get().to(audit))
+        ).await;
+
+        for id in &["1", "2"] {
+            let req = test::TestRequest::get().uri("/").header("x-request-id", *id).to_request();
+            let res = test::call_service(&mut app, req).await;
+            assert_eq!(StatusCode::OK, res.status());
+            assert_eq!(format!("audit {}", id).as_bytes(), &test::read_body(res).await[..]);
+        }
+        assert_eq!(2, CALLS.load(Ordering::SeqCst));
+
+        let res = test::call_service(&mut app, test::TestRequest::get().uri("/").to_request()).await;
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, res.status());
+        assert_eq!(
+            "Cannot initialize dependency: inspirer_actix_ext_core::scope::tests::RequestId, cause: missing request id".as_bytes(),
+            &test::read_body(res).await[..]
+        );
+    }
+
+    /// 模拟数据库事务：异步开启且无法 `Clone`
+    struct Transaction {
+        id: usize,
+        statements: Vec<String>,
+    }
+
+    struct OrderService(Scoped<Transaction>);
+
+    impl IntoService<(Scoped<Transaction>,)> for OrderService {
+        fn init(deps: (Scoped<Transaction>,)) -> Self {
+            OrderService(deps.0)
+        }
+    }
+
+    struct StockService(Scoped<Transaction>);
+
+    impl IntoService<(Scoped<Transaction>,)> for StockService {
+        fn init(deps: (Scoped<Transaction>,)) -> Self {
+            StockService(deps.0)
+        }
+    }
+
+    async fn create_order(tx: Scoped<Transaction>, srv: Service) -> Result<String, Error> {
+        let order_service = srv.get::<_, OrderService>()?;
+        order_service.0.lock().await.unwrap().statements.push("insert order".into());
+
+        let stock_service = srv.get::<_, StockService>()?;
+        stock_service.0.lock().await.unwrap().statements.push("update stock".into());
+
+        let committed = tx.take().await.unwrap();
+        assert!(order_service.0.lock().await.is_none());
+
+        Ok(format!("{}: {}", committed.id, committed.statements.join(", ")))
+    }
+
+    #[actix_rt::test]
+    async fn test_async_scoped_module() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.register_scoped_async(|_: &HttpRequest| async {
+            let id = BEGINS.fetch_add(1, Ordering::SeqCst) + 1;
+            futures_timer::Delay::new(std::time::Duration::from_millis(1)).await;
+            Ok::<_, io::Error>(Transaction { id, statements: vec![] })
+        }.boxed_local());
+
+        let container = module_provider.into_module_container();
+        let mut app = test::init_service(
+            App::new()
+                .configure(container.module_provider())
+                .route("/orders", web::post().to(create_order))
+                .route("/stock", web::get().to(|srv: Service| async move {
+                    srv.get::<_, StockService>().map(|_| "unreachable")
+                }))
+        ).await;
+
+        for id in 1..=2 {
+            let res = test::call_service(&mut app, test::TestRequest::post().uri("/orders").to_request()).await;
+            assert_eq!(StatusCode::OK, res.status());
+            assert_eq!(format!("{}: insert order, update stock", id).as_bytes(), &test::read_body(res).await[..]);
+        }
+        assert_eq!(2, BEGINS.load(Ordering::SeqCst));
+
+        let res = test::call_service(&mut app, test::TestRequest::get().uri("/stock").to_request()).await;
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, res.status());
+        assert_eq!(2, BEGINS.load(Ordering::SeqCst));
+    }
+}
@@ -23,9 +23,38 @@
 //!     // 使用 demo_service 的方法
 //! }
 //! ```
+//!
+//! 服务层同样可以脱离 HTTP 请求使用，例如在命令行工具或后台任务中直接通过模块容器构建：
+//!
+//! ```ignore
+//! let container = module_provider.into_module_container();
+//! let demo_service = container.service::<_, DemoService>()?;
+//! ```
+//!
+//! 此时依赖将直接从容器中解析，请求作用域模块不可用。
+//!
+//! 同一类型注册了多个具名模块时，可通过 [`Named`] 配合限定名注入指定的实例：
+//!
+//! ```ignore
+//! use inspirer_actix_ext_core::qualifier;
+//! use inspirer_actix_ext_core::service::{IntoService, Named};
+//!
+//! qualifier!(pub Replica = "replica");
+//!
+//! pub struct ReportService (MySqlPool, Named<MySqlPool, Replica>);
+//!
+//! impl IntoService<(MySqlPool, Named<MySqlPool, Replica>)> for ReportService {
+//!     fn init(deps: (MySqlPool, Named<MySqlPool, Replica>)) -> Self {
+//!         ReportService (deps.0, deps.1)
+//!     }
+//! }
+//! ```
 
 
 use std::any::type_name;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
 
 use actix_web::{FromRequest, HttpRequest};
 use actix_web::dev::Payload;
@@ -33,6 +62,8 @@ use actix_web::web::Data;
 use futures::future::{ok, Ready};
 
 use crate::error::Error;
+use crate::module::{ModuleContainer, ModuleKey};
+use crate::scope::resolve_scoped;
 
 /// 应用 Service 层提供者
 pub struct Service (HttpRequest);
@@ -43,6 +74,13 @@ impl Service {
     }
 }
 
+impl ModuleContainer {
+    /// 在请求之外构建服务，依赖直接从容器中解析
+    pub fn service<D, S: DependencyFactory<D>>(&self) -> Result<S, Error> {
+        S::make(self)
+    }
+}
+
 impl FromRequest for Service
 {
     type Error = actix_web::Error;
@@ -59,23 +97,153 @@ pub trait IntoService<T> {
     fn init(deps: T) -> Self;
 }
 
+/// 依赖解析上下文
+///
+/// 在请求处理过程中基于当前请求解析，在请求之外（命令行、后台任务、测试等）基于模块容器解析。
+#[derive(Clone, Copy)]
+pub enum ResolveContext<'a> {
+    Request(&'a HttpRequest),
+    Container(&'a ModuleContainer),
+}
+
+impl<'a> ResolveContext<'a> {
+    /// 当前请求，在请求之外解析时不存在
+    pub fn request(&self) -> Option<&'a HttpRequest> {
+        match self {
+            ResolveContext::Request(req) => Some(req),
+            ResolveContext::Container(_) => None,
+        }
+    }
+
+    /// 模块容器，请求中从应用数据获取
+    pub fn container(&self) -> Option<&'a ModuleContainer> {
+        match self {
+            ResolveContext::Request(req) => req.app_data::<Data<ModuleContainer>>().map(|container| container.get_ref()),
+            ResolveContext::Container(container) => Some(container),
+        }
+    }
+}
+
+impl<'a> From<&'a HttpRequest> for ResolveContext<'a> {
+    fn from(req: &'a HttpRequest) -> Self {
+        ResolveContext::Request(req)
+    }
+}
+
+impl<'a> From<&'a ModuleContainer> for ResolveContext<'a> {
+    fn from(container: &'a ModuleContainer) -> Self {
+        ResolveContext::Container(container)
+    }
+}
+
 /// 依赖工厂
 pub trait DependencyFactory<D> {
-    fn make(req: &HttpRequest) -> Result<Self, Error> where Self: Sized;
+    fn make<'a, Ctx: Into<ResolveContext<'a>>>(ctx: Ctx) -> Result<Self, Error> where Self: Sized;
+}
+
+/// 可注入服务的依赖
+///
+/// 请求中默认从 actix web 的应用数据中获取 `Data<T>` 的副本，不存在时尝试解析请求作用域的模块；
+/// 请求之外直接从模块容器中获取。
+pub trait Dependency: Sized {
+    fn resolve(ctx: ResolveContext<'_>) -> Result<Self, Error>;
+}
+
+impl<T> Dependency for T
+    where T: Clone + 'static
+{
+    fn resolve(ctx: ResolveContext<'_>) -> Result<Self, Error> {
+        match ctx {
+            ResolveContext::Request(req) => match req.app_data::<Data<T>>() {
+                Some(module) => Ok(module.get_ref().clone()),
+                None => resolve_scoped::<T>(req),
+            },
+            ResolveContext::Container(container) => container.module::<T>(&ModuleKey::of::<T>())
+                .ok_or(Error::DependencyNotFound(type_name::<T>())),
+        }
+    }
+}
+
+/// 模块限定名
+///
+/// 用于在依赖注入时指定具名模块的名称，通常通过 [`qualifier!`](crate::qualifier) 宏声明。
+pub trait Qualifier {
+    const NAME: &'static str;
+}
+
+/// 声明模块限定名
+///
+/// ```
+/// use inspirer_actix_ext_core::qualifier;
+/// use inspirer_actix_ext_core::service::Qualifier;
+///
+/// qualifier!(pub Replica = "replica");
+///
+/// assert_eq!("replica", Replica::NAME);
+/// ```
+#[macro_export]
+macro_rules! qualifier {
+    ($(#[$meta:meta])* $vis:vis $ident:ident = $name:expr) => {
+        $(#[$meta])*
+        $vis struct $ident;
+
+        impl $crate::service::Qualifier for $ident {
+            const NAME: &'static str = $name;
+        }
+    };
+}
+
+/// 具名模块依赖
+///
+/// 从模块容器中解析以限定名 `Q` 注册的模块 `T`。
+pub struct Named<T, Q> {
+    module: T,
+    _qualifier: PhantomData<fn() -> Q>,
+}
+
+impl<T, Q> Named<T, Q> {
+    pub fn into_inner(self) -> T {
+        self.module
+    }
+}
+
+impl<T, Q> Deref for Named<T, Q> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.module
+    }
+}
+
+impl<T: fmt::Debug, Q: Qualifier> fmt::Debug for Named<T, Q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Named").field(&Q::NAME).field(&self.module).finish()
+    }
+}
+
+impl<T, Q> Dependency for Named<T, Q>
+    where T: Send + Sync + Clone + 'static,
+          Q: Qualifier
+{
+    fn resolve(ctx: ResolveContext<'_>) -> Result<Self, Error> {
+        ctx.container()
+            .and_then(|container| container.get_named::<T>(Q::NAME))
+            .map(|module| Named { module, _qualifier: PhantomData })
+            .ok_or(Error::NamedDependencyNotFound(type_name::<T>(), Q::NAME))
+    }
 }
 
 macro_rules! factory_tuple {
     ($($T:ident),+) => {
         impl<S, $($T,)+> DependencyFactory<($($T,)+)> for S
         where S: IntoService<($($T,)+)>,
-            $($T: Clone + 'static,)+
+            $($T: Dependency,)+
         {
-            fn make(req: &HttpRequest) -> Result<Self, Error> {
+            fn make<'a, Ctx: Into<ResolveContext<'a>>>(ctx: Ctx) -> Result<Self, Error> {
+                let ctx = ctx.into();
                 let deps = (
                     $(
-                        req.app_data::<Data<$T>>()
-                            .map(|c| c.get_ref().clone())
-                            .ok_or(Error::DependencyNotFound(type_name::<$T>()))?,
+                        $T::resolve(ctx)?,
                     )+
                 );
 
@@ -88,7 +256,7 @@ macro_rules! factory_tuple {
 impl<S> DependencyFactory<()> for S
     where S: IntoService<()>
 {
-    fn make(_req: &HttpRequest) -> Result<Self, Error> {
+    fn make<'a, Ctx: Into<ResolveContext<'a>>>(_ctx: Ctx) -> Result<Self, Error> {
         Ok(S::init(()))
     }
 }
@@ -101,4 +269,81 @@ factory_tuple!(A, B, C, D, E);
 factory_tuple!(A, B, C, D, E, F);
 factory_tuple!(A, B, C, D, E, F, G);
 factory_tuple!(A, B, C, D, E, F, G, H);
-factory_tuple!(A, B, C, D, E, F, G, H, I);
\ No newline at end of file
+factory_tuple!(A, B, C, D, E, F, G, H, I);
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, test, web};
+    use actix_web::http::StatusCode;
+
+    use crate::module::ModuleProvider;
+
+    use super::*;
+
+    qualifier!(Replica = "replica");
+
+    struct ReportService(String, Named<String, Replica>);
+
+    impl IntoService<(String, Named<String, Replica>)> for ReportService {
+        fn init(deps: (String, Named<String, Replica>)) -> Self {
+            ReportService(deps.0, deps.1)
+        }
+    }
+
+    async fn report(srv: Service) -> Result<String, Error> {
+        let report_service = srv.get::<_, ReportService>()?;
+        Ok(format!("{} {}", report_service.0, *report_service.1))
+    }
+
+    async fn call(module_provider: ModuleProvider) -> (StatusCode, String) {
+        let container = module_provider.into_module_container();
+        let mut app = test::init_service(
+            App::new()
+                .configure(container.module_provider())
+                .route("/", web::get().to(report))
+        ).await;
+
+        let res = test::call_service(&mut app, test::TestRequest::get().uri("/").to_request()).await;
+        let status = res.status();
+        let body = test::read_body(res).await;
+
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[actix_rt::test]
+    async fn test_named_dependency() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert("primary".to_string());
+        module_provider.insert_named("replica", "replica".to_string());
+
+        assert_eq!((StatusCode::OK, "primary replica".to_string()), call(module_provider).await);
+    }
+
+    #[actix_rt::test]
+    async fn test_named_dependency_not_found() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert("primary".to_string());
+        module_provider.insert_named("analytics", "analytics".to_string());
+
+        assert_eq!(
+            (StatusCode::INTERNAL_SERVER_ERROR, "Cannot found dependency: alloc::string::String named [replica]".to_string()),
+            call(module_provider).await
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_service_from_container() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert("primary".to_string());
+        module_provider.insert_named("replica", "replica".to_string());
+
+        let container = module_provider.into_module_container();
+        assert_eq!(Some("primary".to_string()), container.get::<String>());
+
+        let report_service = container.service::<_, ReportService>().unwrap();
+        assert_eq!("primary replica", format!("{} {}", report_service.0, *report_service.1));
+
+        let container = ModuleProvider::new().into_module_container();
+        assert!(matches!(container.service::<_, ReportService>(), Err(Error::DependencyNotFound(_))));
+    }
+}
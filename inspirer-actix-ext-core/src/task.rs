@@ -0,0 +1,433 @@
+//! 后台任务
+//!
+//! 缓存预热、队列消费等需要与 actix web 服务一同运行的任务，可以通过
+//! [`ModuleProvider::register_task`](crate::module::ModuleProvider::register_task) 注册：
+//!
+//! ```ignore
+//! module_provider.register_task(BackgroundTask::new("cache-warmer", |ctx: TaskContext| async move {
+//!     let pool = ctx.container().get::<MySqlPool>().unwrap();
+//!
+//!     while !ctx.is_cancelled() {
+//!         warm_up(&pool).await?;
+//!         select(Box::pin(ctx.cancelled()), Delay::new(Duration::from_secs(60))).await;
+//!     }
+//!
+//!     Ok(())
+//! }));
+//! ```
+//!
+//! 任务在 [`ModuleContainer::serve`] 就绪钩子执行完毕后启动，也可以通过 [`ModuleContainer::start_tasks`]
+//! 在请求之外（例如独立的 worker 进程）启动。任务返回错误或 panic 时按退避策略重启，
+//! 应用停止时随模块关闭钩子（按注册顺序逆序）取消，任务需通过 [`TaskContext`] 观察取消信号并自行退出。
+//!
+//! 任务的运行状态将反映在健康检查中：重启等待期间就绪探针失败，放弃重启后存活探针失败。
+
+use std::any::{Any, type_name};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use actix_web::rt;
+use actix_web::web::ServiceConfig;
+use futures::channel::oneshot;
+use futures::future::{Either, FutureExt, LocalBoxFuture, ok, select, Shared};
+use futures_timer::Delay;
+use serde::Serialize;
+
+use crate::module::{ModuleContainer, ModuleRegister};
+use crate::retry::RetryPolicy;
+
+type TaskFn = Box<dyn Fn(TaskContext) -> LocalBoxFuture<'static, anyhow::Result<()>> + Send + Sync>;
+
+/// 取消信号
+#[derive(Clone)]
+pub struct CancellationToken {
+    sender: Arc<Mutex<Option<oneshot::Sender<()>>>>,
+    receiver: Shared<oneshot::Receiver<()>>,
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        CancellationToken::new()
+    }
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        let (sender, receiver) = oneshot::channel();
+        CancellationToken {
+            sender: Arc::new(Mutex::new(Some(sender))),
+            receiver: receiver.shared(),
+        }
+    }
+
+    /// 发出取消信号
+    pub fn cancel(&self) {
+        self.sender.lock().unwrap().take();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.sender.lock().unwrap().is_none()
+    }
+
+    /// 等待取消信号
+    pub async fn cancelled(&self) {
+        let _ = self.receiver.clone().await;
+    }
+}
+
+/// 任务上下文
+#[derive(Clone)]
+pub struct TaskContext {
+    container: ModuleContainer,
+    cancellation: CancellationToken,
+}
+
+impl TaskContext {
+    /// 启动任务的模块容器
+    pub fn container(&self) -> &ModuleContainer {
+        &self.container
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// 等待取消信号，通常与任务的等待操作一同 `select`
+    pub async fn cancelled(&self) {
+        self.cancellation.cancelled().await
+    }
+}
+
+/// 任务运行状态
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskState {
+    /// 尚未启动
+    Pending,
+    Running,
+    /// 失败后等待重启
+    Restarting,
+    /// 正常结束
+    Finished,
+    Cancelled,
+    /// 达到最大执行次数，不再重启
+    Failed,
+}
+
+/// 任务状态
+#[derive(Serialize, Debug, Clone)]
+pub struct TaskStatus {
+    pub name: String,
+    pub state: TaskState,
+    pub restarts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// 后台任务
+pub struct BackgroundTask {
+    name: String,
+    run: TaskFn,
+    policy: Option<RetryPolicy>,
+    shutdown_timeout: Duration,
+    status: Mutex<TaskStatus>,
+    cancellation: CancellationToken,
+    done: Mutex<Option<Shared<oneshot::Receiver<()>>>>,
+}
+
+impl BackgroundTask {
+    pub fn new<F, R>(name: &str, task: F) -> Self
+        where F: Fn(TaskContext) -> R + Send + Sync + 'static,
+              R: Future<Output=anyhow::Result<()>> + 'static
+    {
+        BackgroundTask {
+            name: name.to_string(),
+            run: Box::new(move |ctx| task(ctx).boxed_local()),
+            policy: None,
+            shutdown_timeout: Duration::from_secs(30),
+            status: Mutex::new(TaskStatus {
+                name: name.to_string(),
+                state: TaskState::Pending,
+                restarts: 0,
+                last_error: None,
+            }),
+            cancellation: CancellationToken::new(),
+            done: Mutex::new(None),
+        }
+    }
+
+    /// 重启策略
+    ///
+    /// 按策略的退避参数等待后重启，执行次数达到 `max_attempts` 后不再重启。
+    /// 未指定时使用默认的退避参数且不限制重启次数。
+    pub fn restart(mut self, policy: RetryPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// 应用停止时等待任务退出的时间上限，默认 30 秒
+    pub fn shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn status(&self) -> TaskStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    fn update<F: FnOnce(&mut TaskStatus)>(&self, update: F) {
+        update(&mut self.status.lock().unwrap())
+    }
+
+    fn spawn(self: Arc<Self>, container: ModuleContainer) {
+        let mut done = self.done.lock().unwrap();
+        if done.is_some() {
+            return;
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        *done = Some(receiver.shared());
+
+        info!("Start background task [{}].", self.name);
+        let task = self.clone();
+        rt::spawn(async move {
+            task.supervise(container).await;
+            let _ = sender.send(());
+        });
+    }
+
+    async fn supervise(&self, container: ModuleContainer) {
+        let ctx = TaskContext { container, cancellation: self.cancellation.clone() };
+        let policy = self.policy.clone().unwrap_or_default();
+        let mut failures = 0;
+
+        loop {
+            self.update(|status| status.state = TaskState::Running);
+
+            let result = AssertUnwindSafe(async { (self.run)(ctx.clone()).await })
+                .catch_unwind()
+                .await;
+
+            let error = match result {
+                Ok(Ok(())) => None,
+                Ok(Err(err)) => Some(format!("{:#}", err)),
+                Err(panic) => Some(format!("panicked: {}", panic_message(panic.as_ref()))),
+            };
+
+            if ctx.is_cancelled() {
+                self.update(|status| status.state = TaskState::Cancelled);
+                break;
+            }
+
+            let error = match error {
+                Some(error) => error,
+                None => {
+                    info!("Background task [{}] finished.", self.name);
+                    self.update(|status| status.state = TaskState::Finished);
+                    break;
+                }
+            };
+
+            failures += 1;
+            if matches!(&self.policy, Some(policy) if failures >= policy.max_attempts) {
+                error!("Background task [{}] failed after {} attempt(s), give up: {}", self.name, failures, error);
+                self.update(|status| {
+                    status.state = TaskState::Failed;
+                    status.last_error = Some(error);
+                });
+                break;
+            }
+
+            let backoff = policy.backoff_for(failures);
+            warn!("Background task [{}] failed, restart in {:?}: {}", self.name, backoff, error);
+            self.update(|status| {
+                status.state = TaskState::Restarting;
+                status.restarts = failures;
+                status.last_error = Some(error);
+            });
+
+            if let Either::Left(_) = select(Box::pin(ctx.cancelled()), Delay::new(backoff)).await {
+                self.update(|status| status.state = TaskState::Cancelled);
+                break;
+            }
+        }
+    }
+
+    async fn stop(&self) {
+        self.cancellation.cancel();
+
+        let done = self.done.lock().unwrap().clone();
+        if let Some(done) = done {
+            if let Either::Right(_) = select(done, Delay::new(self.shutdown_timeout)).await {
+                warn!("Background task [{}] did not stop within {:?}.", self.name, self.shutdown_timeout);
+            }
+        }
+    }
+
+    fn probe(&self, down: &[TaskState]) -> LocalBoxFuture<'static, anyhow::Result<()>> {
+        let status = self.status();
+        if !down.contains(&status.state) {
+            return ok(()).boxed_local();
+        }
+
+        let err = anyhow::anyhow!(
+            "Background task is {:?}: {}",
+            status.state,
+            status.last_error.unwrap_or_default()
+        );
+        futures::future::err(err).boxed_local()
+    }
+}
+
+pub(crate) fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic.downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+/// 后台任务注册器
+pub(crate) struct TaskModuleRegister(pub Arc<BackgroundTask>);
+
+impl ModuleRegister for TaskModuleRegister {
+    fn register(&self, _service: &mut ServiceConfig) {}
+
+    fn get_module(&self) -> Box<dyn Any> {
+        Box::new(self.0.clone())
+    }
+
+    fn module_name(&self) -> &'static str {
+        type_name::<BackgroundTask>()
+    }
+
+    fn module_qualifier(&self) -> Option<&str> {
+        Some(self.0.name())
+    }
+
+    fn on_shutdown(&self) -> LocalBoxFuture<'static, anyhow::Result<()>> {
+        let task = self.0.clone();
+        async move {
+            task.stop().await;
+            Ok(())
+        }.boxed_local()
+    }
+
+    fn liveness_probe(&self) -> Option<LocalBoxFuture<'static, anyhow::Result<()>>> {
+        Some(self.0.probe(&[TaskState::Failed]))
+    }
+
+    fn readiness_probe(&self) -> Option<LocalBoxFuture<'static, anyhow::Result<()>>> {
+        Some(self.0.probe(&[TaskState::Restarting, TaskState::Failed]))
+    }
+}
+
+impl ModuleContainer {
+    fn background_tasks(&self) -> impl Iterator<Item=Arc<BackgroundTask>> + '_ {
+        self.registers()
+            .iter()
+            .filter_map(|register| register.get_module().downcast::<Arc<BackgroundTask>>().ok())
+            .map(|task| *task)
+    }
+
+    /// 启动所有后台任务，已启动的任务将被忽略
+    ///
+    /// 需要在 actix 运行时中调用，[`ModuleContainer::serve`] 会自动调用该方法。
+    pub fn start_tasks(&self) {
+        for task in self.background_tasks() {
+            task.spawn(self.clone());
+        }
+    }
+
+    /// 所有后台任务的状态
+    pub fn tasks(&self) -> Vec<TaskStatus> {
+        self.background_tasks().map(|task| task.status()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::health::HealthStatus;
+    use crate::module::ModuleProvider;
+
+    use super::*;
+
+    async fn wait_for<F: Fn() -> bool>(condition: F) {
+        for _ in 0..200 {
+            if condition() {
+                return;
+            }
+            Delay::new(Duration::from_millis(5)).await;
+        }
+        panic!("condition not satisfied");
+    }
+
+    fn state(container: &ModuleContainer, name: &str) -> TaskStatus {
+        container.tasks().into_iter().find(|status| status.name == name).unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn test_restart_and_cancel() {
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert("consumer".to_string());
+        module_provider.register_task(
+            BackgroundTask::new("consumer", |ctx: TaskContext| async move {
+                assert_eq!(Some("consumer".to_string()), ctx.container().get::<String>());
+                if RUNS.fetch_add(1, Ordering::SeqCst) < 2 {
+                    anyhow::bail!("connection refused");
+                }
+
+                ctx.cancelled().await;
+                Ok(())
+            })
+                .restart(RetryPolicy::new().max_attempts(5).backoff(Duration::from_millis(1), Duration::from_millis(1)).jitter(0.0))
+        );
+
+        let container = module_provider.into_module_container();
+        assert_eq!(TaskState::Pending, state(&container, "consumer").state);
+
+        container.start_tasks();
+        wait_for(|| RUNS.load(Ordering::SeqCst) == 3).await;
+
+        let status = state(&container, "consumer");
+        assert_eq!(TaskState::Running, status.state);
+        assert_eq!(2, status.restarts);
+        assert_eq!(Some("connection refused".to_string()), status.last_error);
+        assert!(container.readiness().await.is_up());
+
+        container.shutdown().await;
+        assert_eq!(TaskState::Cancelled, state(&container, "consumer").state);
+    }
+
+    #[actix_rt::test]
+    async fn test_give_up_after_panic() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.register_task(
+            BackgroundTask::new("warmer", |_: TaskContext| async move {
+                panic!("cache unavailable");
+            })
+                .restart(RetryPolicy::new().max_attempts(2).backoff(Duration::from_millis(1), Duration::from_millis(1)))
+        );
+
+        let container = module_provider.into_module_container();
+        container.start_tasks();
+        wait_for(|| state(&container, "warmer").state == TaskState::Failed).await;
+
+        let status = state(&container, "warmer");
+        assert_eq!(1, status.restarts);
+        assert_eq!(Some("panicked: cache unavailable".to_string()), status.last_error);
+
+        let report = container.liveness().await;
+        assert_eq!(HealthStatus::Down, report.status);
+        assert_eq!(Some("warmer"), report.modules[0].name.as_deref());
+    }
+}
@@ -0,0 +1,102 @@
+//! 测试辅助
+//!
+//! 需要启用 `testing` 特性，通常仅在 `dev-dependencies` 中启用：
+//!
+//! ```toml
+//! [dev-dependencies]
+//! inspirer-actix-ext = { version = "0.1", features = ["testing"] }
+//! ```
+//!
+//! 基于模块容器构建 actix web 测试应用，配合 [`ModuleProvider::override_with`] 或
+//! [`ModuleContainer::with_override`] 使用，可以在复用应用启动流程的同时以测试替身替换真实的模块：
+//!
+//! ```ignore
+//! let container = bootstrap().await?.with_override(FakeMailer::default());
+//! let mut app = testing::init_service(&container, routes).await;
+//!
+//! let res = test::call_service(&mut app, TestRequest::post().uri("/register").to_request()).await;
+//! ```
+//!
+//! [`ModuleProvider::override_with`]: crate::module::ModuleProvider::override_with
+
+use actix_http::Request;
+use actix_web::{App, test};
+use actix_web::dev::{Body, Service, ServiceResponse};
+use actix_web::web::ServiceConfig;
+
+use crate::module::ModuleContainer;
+
+/// 以模块容器及路由配置初始化测试服务
+pub async fn init_service<F>(container: &ModuleContainer, configure: F)
+    -> impl Service<Request=Request, Response=ServiceResponse<Body>, Error=actix_web::Error>
+    where F: FnOnce(&mut ServiceConfig)
+{
+    test::init_service(
+        App::new()
+            .configure(container.module_provider())
+            .configure(configure)
+    ).await
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::web;
+
+    use crate::error::Error;
+    use crate::module::ModuleProvider;
+    use crate::service::{IntoService, Service as AppService};
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct Mailer(&'static str);
+
+    struct RegisterService(Mailer);
+
+    impl IntoService<(Mailer, )> for RegisterService {
+        fn init(deps: (Mailer, )) -> Self {
+            RegisterService(deps.0)
+        }
+    }
+
+    async fn smtp_mailer(_: &ModuleProvider) -> std::io::Result<Mailer> {
+        Err(std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "smtp server is unreachable"))
+    }
+
+    async fn bootstrap(mut module_provider: ModuleProvider) -> anyhow::Result<ModuleContainer> {
+        module_provider.register(smtp_mailer).await?;
+        Ok(module_provider.into_module_container())
+    }
+
+    fn routes(config: &mut ServiceConfig) {
+        config.route("/register", web::post().to(|srv: AppService| async move {
+            let register_service = srv.get::<_, RegisterService>()?;
+            Ok::<_, Error>(format!("sent by {}", (register_service.0).0))
+        }));
+    }
+
+    async fn call(container: &ModuleContainer) -> String {
+        let mut app = init_service(container, routes).await;
+        let res = test::call_service(&mut app, test::TestRequest::post().uri("/register").to_request()).await;
+        String::from_utf8(test::read_body(res).await.to_vec()).unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn test_provider_override() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.override_with(Mailer("fake mailer"));
+
+        let container = bootstrap(module_provider).await.unwrap();
+        assert_eq!("sent by fake mailer", call(&container).await);
+    }
+
+    #[actix_rt::test]
+    async fn test_container_override() {
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert(Mailer("smtp mailer"));
+
+        let container = module_provider.into_module_container();
+        assert_eq!("sent by smtp mailer", call(&container).await);
+        assert_eq!("sent by fake mailer", call(&container.with_override(Mailer("fake mailer"))).await);
+    }
+}
@@ -0,0 +1,145 @@
+use proc_macro2::TokenStream;
+
+struct ConfigAttribute {
+    key: syn::LitStr,
+    krate: syn::Path,
+    default: bool,
+    validate: bool,
+}
+
+fn parse_config_attribute(input: &syn::DeriveInput) -> syn::Result<ConfigAttribute> {
+    let (mut key, mut krate, mut default, mut validate) = (None, None, false, false);
+
+    for attr in input.attrs.iter().filter(|attr| attr.path.is_ident("config")) {
+        let list = match attr.parse_meta()? {
+            syn::Meta::List(list) => list,
+            meta => return Err(syn::Error::new_spanned(meta, "expected #[config(key = \"...\")]")),
+        };
+
+        for nested in list.nested.iter() {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) if name_value.path.is_ident("key") => {
+                    match &name_value.lit {
+                        syn::Lit::Str(lit) => key = Some(lit.clone()),
+                        lit => return Err(syn::Error::new_spanned(lit, "config key must be a string literal")),
+                    }
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) if name_value.path.is_ident("crate") => {
+                    match &name_value.lit {
+                        syn::Lit::Str(lit) => krate = Some(lit.parse::<syn::Path>()?),
+                        lit => return Err(syn::Error::new_spanned(lit, "config crate must be a string literal")),
+                    }
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("default") => default = true,
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("validate") => validate = true,
+                nested => return Err(syn::Error::new_spanned(nested, "unknown config attribute, expected `key`, `crate`, `default` or `validate`")),
+            }
+        }
+    }
+
+    match key {
+        Some(key) => Ok(ConfigAttribute {
+            key,
+            krate: krate.unwrap_or_else(|| syn::parse_quote!(inspirer_actix_ext)),
+            default,
+            validate,
+        }),
+        None => Err(syn::Error::new_spanned(&input.ident, "missing #[config(key = \"...\")] attribute")),
+    }
+}
+
+pub fn expand_config_section_derive(input: &mut syn::DeriveInput) -> TokenStream {
+    let attribute = match parse_config_attribute(input) {
+        Ok(attribute) => attribute,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let ident = input.ident.clone();
+    let key = attribute.key;
+    let krate = attribute.krate;
+    let missing = if attribute.default {
+        quote! {
+            fn missing() -> Option<Self> {
+                Some(<Self as Default>::default())
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let validate = if attribute.validate {
+        quote! {
+            fn validate_section(&self) -> Result<(), #krate::config::ValidationErrors> {
+                #krate::config::Validate::validate(self)
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        impl #krate::config::ConfigSection for #ident {
+            const KEY: &'static str = #key;
+
+            #missing
+
+            #validate
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(input: syn::DeriveInput) -> String {
+        let mut input = input;
+        expand_config_section_derive(&mut input).to_string()
+    }
+
+    #[test]
+    fn test_expand() {
+        let expanded = expand(syn::parse_quote! {
+            #[config(key = "mail")]
+            struct MailConfig {
+                host: String,
+            }
+        });
+        assert!(expanded.contains("impl inspirer_actix_ext :: config :: ConfigSection for MailConfig"));
+        assert!(expanded.contains("const KEY : & 'static str = \"mail\""));
+        assert!(!expanded.contains("fn missing"));
+        assert!(!expanded.contains("fn validate_section"));
+
+        let expanded = expand(syn::parse_quote! {
+            #[config(key = "database", crate = "inspirer_actix_ext_core", default, validate)]
+            struct DatabaseConfig {
+                port: u16,
+            }
+        });
+        assert!(expanded.contains("impl inspirer_actix_ext_core :: config :: ConfigSection for DatabaseConfig"));
+        assert!(expanded.contains("fn missing () -> Option < Self > { Some (< Self as Default > :: default ()) }"));
+        assert!(expanded.contains("fn validate_section (& self) -> Result < () , inspirer_actix_ext_core :: config :: ValidationErrors >"));
+        assert!(expanded.contains("inspirer_actix_ext_core :: config :: Validate :: validate (self)"));
+    }
+
+    #[test]
+    fn test_expand_error() {
+        let expanded = expand(syn::parse_quote! {
+            struct MailConfig;
+        });
+        assert!(expanded.contains("compile_error"));
+        assert!(expanded.contains("missing #[config(key = \\\"...\\\")] attribute"));
+
+        let expanded = expand(syn::parse_quote! {
+            #[config(key = "mail", reload)]
+            struct MailConfig;
+        });
+        assert!(expanded.contains("unknown config attribute"));
+
+        let expanded = expand(syn::parse_quote! {
+            #[config(key = 1)]
+            struct MailConfig;
+        });
+        assert!(expanded.contains("config key must be a string literal"));
+    }
+}
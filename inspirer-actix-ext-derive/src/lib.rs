@@ -7,6 +7,7 @@ use proc_macro::TokenStream;
 use syn::DeriveInput;
 
 mod service;
+mod config;
 
 #[proc_macro_derive(Service)]
 pub fn service_derive(input: TokenStream) -> TokenStream {
@@ -24,6 +25,17 @@ pub fn from_request_service_derive(input: TokenStream) -> TokenStream {
     service::expand_from_request_service_derive(&mut input).into()
 }
 
+/// 为配置节实现 `ConfigSection`，通过 `#[config(key = "...")]` 指定配置键，
+/// 追加 `default` 时配置缺失将使用 `Default` 实现，追加 `validate` 时注册前通过 `Validate` 校验；
+/// 仅依赖 `inspirer-actix-ext-core` 的 crate 需指定 `crate = "inspirer_actix_ext_core"`
+#[proc_macro_derive(ConfigSection, attributes(config))]
+pub fn config_section_derive(input: TokenStream) -> TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+    let mut input = syn::parse2::<DeriveInput>(input).unwrap();
+
+    config::expand_config_section_derive(&mut input).into()
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
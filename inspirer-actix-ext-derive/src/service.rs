@@ -12,12 +12,7 @@ pub fn expand_service_derive(
                     for (offset, v) in fields_named.named.iter().enumerate() {
                         key.push(syn::LitInt::new(&format!("{}", offset), proc_macro2::Span::call_site()));
                         field.push(v.ident.clone().unwrap());
-                        target.push(match &v.ty {
-                            syn::Type::Path(path) => {
-                                path.path.segments.first().unwrap().ident.clone()
-                            }
-                            _ => panic!()
-                        })
+                        target.push(v.ty.clone())
                     }
 
                     let block = quote!{
@@ -32,12 +27,7 @@ pub fn expand_service_derive(
                     let (mut key, mut target) = (vec![], vec![]);
                     for (offset, v) in fields_unnamed.unnamed.iter().enumerate() {
                         key.push(syn::LitInt::new(&format!("{}", offset), proc_macro2::Span::call_site()));
-                        target.push(match &v.ty {
-                            syn::Type::Path(path) => {
-                                path.path.segments.first().unwrap().ident.clone()
-                            }
-                            _ => panic!()
-                        })
+                        target.push(v.ty.clone())
                     }
 
                     let block = quote!{
@@ -1,16 +1,26 @@
+use inspirer_actix_ext_derive::ConfigSection;
 use serde::{Deserialize, Serialize};
 use sqlx::mysql::MySqlConnectOptions;
+use validator::Validate;
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Validate, ConfigSection)]
+#[config(key = "database", crate = "inspirer_actix_ext_core", validate)]
 pub struct DatabaseConfig {
+    #[validate(length(min = 1, message = "host must not be empty"))]
     pub host: String,
+    #[validate(length(min = 1, message = "username must not be empty"))]
     pub username: String,
     pub password: Option<String>,
     pub database: Option<String>,
-    #[serde(default)]
+    #[serde(default = "default_port")]
+    #[validate(range(min = 1, message = "port must be between 1 and 65535"))]
     pub port: u16
 }
 
+fn default_port() -> u16 {
+    3306
+}
+
 impl Default for DatabaseConfig {
     fn default() -> Self {
         DatabaseConfig {
@@ -18,7 +28,7 @@ impl Default for DatabaseConfig {
             username: "root".into(),
             password: None,
             database: None,
-            port: 3306
+            port: default_port()
         }
     }
 }
@@ -42,4 +52,51 @@ impl Into<MySqlConnectOptions> for DatabaseConfig {
 
         options
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use inspirer_actix_ext_core::config::{Config, ConfigSection, File, FileFormat};
+    use inspirer_actix_ext_core::preludes::{BootstrapError, ModuleProvider};
+    use sqlx::MySqlPool;
+
+    use super::*;
+
+    fn provider(toml: &str) -> ModuleProvider {
+        let mut config = Config::new();
+        config.merge(File::from_str(toml, FileFormat::Toml)).unwrap();
+        ModuleProvider::initialize(config)
+    }
+
+    #[test]
+    fn test_default_port() {
+        let module_provider = provider("[database]\nhost = \"db\"\nusername = \"app\"\n");
+        let config = module_provider.validated_config_section::<MySqlPool, DatabaseConfig>(DatabaseConfig::KEY).unwrap();
+        assert_eq!(3306, config.port);
+        assert_eq!(3306, DatabaseConfig::default().port);
+    }
+
+    #[test]
+    fn test_validate() {
+        let module_provider = provider("[database]\nhost = \"\"\nusername = \"app\"\nport = 0\n");
+        let err = module_provider.validated_config_section::<MySqlPool, DatabaseConfig>(DatabaseConfig::KEY).err().unwrap();
+
+        match err {
+            BootstrapError::ConfigViolations { violations, .. } => assert_eq!(vec![
+                "[database.host] host must not be empty".to_string(),
+                "[database.port] port must be between 1 and 65535".to_string(),
+            ], violations),
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_definition_config_check() {
+        let module_provider = provider("[database]\nhost = \"db\"\nusername = \"app\"\nport = 0\n");
+        let failures = crate::module_provider::mysql::definition().check_config(&module_provider);
+
+        assert_eq!(1, failures.len());
+        assert!(failures[0].to_string().ends_with("has 1 config violation(s): [database.port] port must be between 1 and 65535"));
+        assert!(crate::module_provider::mysql::definition().check_config(&provider("")).is_empty());
+    }
+}
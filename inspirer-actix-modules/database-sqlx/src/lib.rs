@@ -11,6 +11,7 @@ pub mod config;
 pub mod module_provider;
 pub mod dao;
 pub mod statement;
+pub mod transaction;
 
 pub mod prelude {
     pub use sqlx;
@@ -19,4 +20,5 @@ pub mod prelude {
     pub use crate::dao::*;
     pub use crate::statement;
     pub use crate::module_provider::mysql;
+    pub use crate::transaction::EventTransaction;
 }
\ No newline at end of file
@@ -1,27 +1,49 @@
 pub mod mysql {
+    use std::future::Future;
+    use std::pin::Pin;
+
     use sqlx::mysql::{MySqlConnectOptions, MySqlPoolOptions};
-    use sqlx::{Result, MySqlPool};
+    use sqlx::MySqlPool;
 
     use inspirer_actix_ext_core::preludes::*;
 
     use crate::config::DatabaseConfig;
-    use inspirer_actix_ext_core::config::Config;
+    use inspirer_actix_ext_core::config::{Config, ConfigSection};
 
-    pub async fn register(ctx: &ModuleProvider) -> Result<MySqlPool> {
+    pub async fn register(ctx: &ModuleProvider) -> Result<MySqlPool, BootstrapError> {
         debug!("Register MySQL database (sqlx) module.");
 
         debug!("Get database config from module provider.");
-        let config = ctx.get_ref::<DatabaseConfig>()
-            .cloned()
-            .or_else(|| {
-                debug!("Module provider is not contain <DatabaseConfig>, load config from <Config> module.");
-                ctx.get_ref::<Config>()
-                    .and_then(|config|
-                        config.get::<DatabaseConfig>("database").ok())
+        let config = ctx.validated_config_section::<MySqlPool, DatabaseConfig>(DatabaseConfig::KEY)?;
+
+        connect(config).await
+            .map_err(BootstrapError::factory_failed::<MySqlPool, _>)
+    }
+
+    /// 具名 MySQL 连接池工厂方法
+    ///
+    /// 优先使用同名的 `DatabaseConfig` 模块，其次读取 `Config` 模块中 `database.{name}` 配置节，
+    /// 用于同时连接多个数据库的场景，例如：
+    ///
+    /// ```ignore
+    /// module_provider.register_named("replica", mysql::named("replica")).await?;
+    /// ```
+    pub fn named(name: &str) -> impl Fn(&ModuleProvider) -> Pin<Box<dyn Future<Output=Result<MySqlPool, BootstrapError>>>> + Clone {
+        let name = name.to_string();
+        move |ctx| {
+            debug!("Register MySQL database (sqlx) module [{}].", name);
+
+            let config = ctx.validated_named_config_section::<MySqlPool, DatabaseConfig>(&name, &format!("{}.{}", DatabaseConfig::KEY, name));
+            let name = name.clone();
+
+            Box::pin(async move {
+                connect(config?).await
+                    .map_err(|err| BootstrapError::named_factory_failed::<MySqlPool, _>(&name, err))
             })
-            .expect("No database connection configuration!")
-            .clone();
+        }
+    }
 
+    async fn connect(config: DatabaseConfig) -> sqlx::Result<MySqlPool> {
         debug!("Convert database config into connect options.");
         let options: MySqlConnectOptions = config.into();
 
@@ -35,4 +57,43 @@ pub mod mysql {
             .connect_with(options)
             .await
     }
+
+    fn hooks() -> ModuleHooks<MySqlPool> {
+        ModuleHooks::new()
+            .on_shutdown(|pool: MySqlPool| async move {
+                info!("Close database (mysql) connection pool.");
+                pool.close().await;
+                Ok(())
+            })
+            .readiness(|pool: MySqlPool| async move {
+                sqlx::query("SELECT 1").execute(&pool).await?;
+                Ok(())
+            })
+    }
+
+    /// MySQL 连接池模块定义
+    ///
+    /// 若同时批量注册了 `DatabaseConfig` 或 `Config` 模块，将保证其先于连接池注册。
+    /// 应用停止时将关闭连接池，等待已借出的连接归还；就绪探针通过执行 `SELECT 1` 检查数据库是否可用。
+    /// 连接失败时按 `database.retry` 配置节的重试策略重试，未配置时不重试。
+    /// 数据库配置将作为配置预检，通过 `Application` 启动时，校验失败将在执行任何模块的工厂方法之前
+    /// 返回全部违反的规则。
+    pub fn definition() -> ModuleDefinition {
+        ModuleDefinition::with_hooks(register, hooks())
+            .after::<DatabaseConfig>()
+            .after::<Config>()
+            .retry_config("database.retry")
+            .validate_config::<MySqlPool, DatabaseConfig>(DatabaseConfig::KEY)
+    }
+
+    /// 具名 MySQL 连接池模块定义，重试策略读取自 `database.{name}.retry` 配置节
+    ///
+    /// 若同时批量注册了同名的 `DatabaseConfig` 或 `Config` 模块，将保证其先于连接池注册。
+    pub fn named_definition(name: &str) -> ModuleDefinition {
+        ModuleDefinition::named_with_hooks(name, named(name), hooks())
+            .after_named::<DatabaseConfig>(name)
+            .after::<Config>()
+            .retry_config(&format!("database.{}.retry", name))
+            .validate_named_config::<MySqlPool, DatabaseConfig>(name, &format!("{}.{}", DatabaseConfig::KEY, name))
+    }
 }
\ No newline at end of file
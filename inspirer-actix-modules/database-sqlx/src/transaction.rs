@@ -0,0 +1,79 @@
+//! 提交后分发事件的事务
+//!
+//! 事务中产生的领域事件应当在事务提交后再分发，避免处理器读取到未提交或已回滚的数据：
+//!
+//! ```ignore
+//! let mut tx = EventTransaction::begin(&pool, &events).await?;
+//! let id = create_user(&mut *tx).await?;
+//! tx.defer(UserCreated { id });
+//! tx.commit().await?;
+//! ```
+//!
+//! 回滚或未提交即丢弃事务时，暂存的事件不会分发。
+
+use std::ops::{Deref, DerefMut};
+
+use inspirer_actix_ext_core::event::{DeferredEvents, Events};
+use sqlx::{MySql, MySqlPool, Transaction};
+
+/// 携带暂存事件的 MySQL 事务
+pub struct EventTransaction<'c> {
+    transaction: Transaction<'c, MySql>,
+    events: DeferredEvents,
+}
+
+impl EventTransaction<'static> {
+    /// 开启事务
+    pub async fn begin(pool: &MySqlPool, events: &Events) -> sqlx::Result<Self> {
+        Ok(EventTransaction {
+            transaction: pool.begin().await?,
+            events: events.deferred(),
+        })
+    }
+}
+
+impl<'c> EventTransaction<'c> {
+    /// 暂存事件，在事务提交后分发
+    pub fn defer<E>(&mut self, event: E)
+        where E: Send + Sync + 'static
+    {
+        self.events.push(event);
+    }
+
+    /// 提交事务并分发暂存的事件
+    ///
+    /// 事件处理器的失败仅记录日志，不影响已提交的事务。
+    pub async fn commit(self) -> sqlx::Result<()> {
+        self.transaction.commit().await?;
+
+        if !self.events.is_empty() {
+            debug!("Transaction committed, publish {} deferred event(s).", self.events.len());
+            self.events.publish().await;
+        }
+
+        Ok(())
+    }
+
+    /// 回滚事务并丢弃暂存的事件
+    pub async fn rollback(self) -> sqlx::Result<()> {
+        if !self.events.is_empty() {
+            debug!("Transaction rolled back, discard {} deferred event(s).", self.events.len());
+        }
+
+        self.transaction.rollback().await
+    }
+}
+
+impl<'c> Deref for EventTransaction<'c> {
+    type Target = Transaction<'c, MySql>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.transaction
+    }
+}
+
+impl<'c> DerefMut for EventTransaction<'c> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.transaction
+    }
+}
@@ -1,6 +1,8 @@
+use inspirer_actix_ext_derive::ConfigSection;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, ConfigSection)]
+#[config(key = "redis", crate = "inspirer_actix_ext_core")]
 pub struct RedisConfig {
     pub connection: String,
 }
@@ -11,4 +13,4 @@ impl Default for RedisConfig {
             connection: "redis://127.0.0.1:6379".into(),
         }
     }
-}
\ No newline at end of file
+}
@@ -1,39 +1,139 @@
+use std::future::Future;
+use std::pin::Pin;
+
 use inspirer_actix_ext_core::preludes::*;
 use crate::config::RedisConfig;
 use redis::{Client, RedisResult};
-use redis::aio::MultiplexedConnection;
-use inspirer_actix_ext_core::config::Config;
+use redis::aio::{ConnectionLike, MultiplexedConnection};
+use inspirer_actix_ext_core::config::{Config, ConfigSection};
 
-pub async fn register_redis_client(ctx: &ModuleProvider) -> RedisResult<Client> {
+pub async fn register_redis_client(ctx: &ModuleProvider) -> Result<Client, BootstrapError> {
     debug!("Register Redis module.");
 
-    let config = ctx.get_ref::<RedisConfig>()
-        .cloned()
-        .or_else(|| {
-            debug!("Module provider is not contain <RedisConfig>, load config from <Config> module.");
-            ctx.get_ref::<Config>()
-                .and_then(|config|
-                    config.get::<RedisConfig>("redis").ok())
-        })
-        .expect("No redis connection configuration!")
-        .clone();
+    let config = ctx.config_section::<Client, RedisConfig>(RedisConfig::KEY)?;
 
     Client::open(config.connection)
+        .map_err(BootstrapError::factory_failed::<Client, _>)
 }
 
-pub async fn register_redis_multiplexed_connection(ctx: &ModuleProvider) -> RedisResult<MultiplexedConnection> {
+pub async fn register_redis_multiplexed_connection(ctx: &ModuleProvider) -> Result<MultiplexedConnection, BootstrapError> {
     debug!("Register Redis (Multiplexed connection) module.");
 
-    match ctx.get_ref::<Client>() {
+    let client = match ctx.get::<Client>() {
         Some(client) => {
             debug!("Exist redis client, use client create connection.");
-            client.get_multiplexed_async_connection().await
+            client
         }
         None => {
             debug!("Not exist redis client.");
-            let client = register_redis_client(ctx).await?;
-
-            client.get_multiplexed_async_connection().await
+            register_redis_client(ctx).await?
         }
+    };
+
+    client.get_multiplexed_async_connection().await
+        .map_err(BootstrapError::factory_failed::<MultiplexedConnection, _>)
+}
+
+/// 具名 Redis 客户端工厂方法
+///
+/// 优先使用同名的 `RedisConfig` 模块，其次读取 `Config` 模块中 `redis.{name}` 配置节。
+pub fn named_redis_client(name: &str) -> impl Fn(&ModuleProvider) -> Pin<Box<dyn Future<Output=Result<Client, BootstrapError>>>> + Clone {
+    let name = name.to_string();
+    move |ctx| {
+        debug!("Register Redis module [{}].", name);
+
+        let client = ctx.named_config_section::<Client, RedisConfig>(&name, &format!("{}.{}", RedisConfig::KEY, name))
+            .and_then(|config| Client::open(config.connection)
+                .map_err(|err| BootstrapError::named_factory_failed::<Client, _>(&name, err)));
+
+        Box::pin(async move { client })
     }
-}
\ No newline at end of file
+}
+
+/// 具名 Redis 多路复用连接工厂方法
+///
+/// 若存在同名的 Redis 客户端模块则复用该客户端，否则按 [`named_redis_client`] 的规则创建客户端。
+pub fn named_redis_multiplexed_connection(name: &str) -> impl Fn(&ModuleProvider) -> Pin<Box<dyn Future<Output=Result<MultiplexedConnection, BootstrapError>>>> + Clone {
+    let name = name.to_string();
+    move |ctx| {
+        debug!("Register Redis (Multiplexed connection) module [{}].", name);
+
+        let client = match ctx.get_named::<Client>(&name) {
+            Some(client) => {
+                debug!("Exist redis client named [{}], use client create connection.", name);
+                Box::pin(async move { Ok(client) })
+            }
+            None => named_redis_client(&name)(ctx),
+        };
+        let name = name.clone();
+
+        Box::pin(async move {
+            client.await?.get_multiplexed_async_connection().await
+                .map_err(|err| BootstrapError::named_factory_failed::<MultiplexedConnection, _>(&name, err))
+        })
+    }
+}
+
+async fn ping<C: ConnectionLike>(conn: &mut C) -> RedisResult<()> {
+    redis::cmd("PING").query_async(conn).await
+}
+
+fn client_hooks() -> ModuleHooks<Client> {
+    ModuleHooks::new()
+        .readiness(|client: Client| async move {
+            let mut conn = client.get_async_connection().await?;
+            ping(&mut conn).await?;
+            Ok(())
+        })
+}
+
+fn multiplexed_connection_hooks() -> ModuleHooks<MultiplexedConnection> {
+    ModuleHooks::new()
+        .readiness(|mut conn: MultiplexedConnection| async move {
+            ping(&mut conn).await?;
+            Ok(())
+        })
+}
+
+/// Redis 客户端模块定义
+///
+/// 就绪探针将建立一个新连接并执行 `PING` 检查 Redis 是否可用，Redis 配置将作为配置预检。
+pub fn client_definition() -> ModuleDefinition {
+    ModuleDefinition::with_hooks(register_redis_client, client_hooks())
+        .after::<RedisConfig>()
+        .after::<Config>()
+        .validate_config::<Client, RedisConfig>(RedisConfig::KEY)
+}
+
+/// 具名 Redis 客户端模块定义，将保证同名的 `RedisConfig` 模块先于客户端注册
+pub fn named_client_definition(name: &str) -> ModuleDefinition {
+    ModuleDefinition::named_with_hooks(name, named_redis_client(name), client_hooks())
+        .after_named::<RedisConfig>(name)
+        .after::<Config>()
+        .validate_named_config::<Client, RedisConfig>(name, &format!("{}.{}", RedisConfig::KEY, name))
+}
+
+/// Redis 多路复用连接模块定义
+///
+/// 若同时批量注册了 Redis 客户端模块，将复用该客户端创建连接。就绪探针通过该连接执行 `PING`。
+/// 连接失败时按 `redis.retry` 配置节的重试策略重试，未配置时不重试。
+pub fn multiplexed_connection_definition() -> ModuleDefinition {
+    ModuleDefinition::with_hooks(register_redis_multiplexed_connection, multiplexed_connection_hooks())
+        .after::<Client>()
+        .after::<RedisConfig>()
+        .after::<Config>()
+        .retry_config("redis.retry")
+        .validate_config::<MultiplexedConnection, RedisConfig>(RedisConfig::KEY)
+}
+
+/// 具名 Redis 多路复用连接模块定义，重试策略读取自 `redis.{name}.retry` 配置节
+///
+/// 若同时批量注册了同名的 Redis 客户端模块，将复用该客户端创建连接。
+pub fn named_multiplexed_connection_definition(name: &str) -> ModuleDefinition {
+    ModuleDefinition::named_with_hooks(name, named_redis_multiplexed_connection(name), multiplexed_connection_hooks())
+        .after_named::<Client>(name)
+        .after_named::<RedisConfig>(name)
+        .after::<Config>()
+        .retry_config(&format!("redis.{}.retry", name))
+        .validate_named_config::<MultiplexedConnection, RedisConfig>(name, &format!("{}.{}", RedisConfig::KEY, name))
+}
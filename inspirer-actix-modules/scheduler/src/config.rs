@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// 调度器配置，对应 `scheduler` 配置节
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    /// 是否启用调度器，关闭后所有任务均不会注册，例如仅在部分副本上运行定时任务
+    pub enabled: bool,
+    /// 分布式锁的键前缀
+    pub lock_prefix: String,
+    /// 各任务的配置，以任务名称为键
+    pub jobs: HashMap<String, JobConfig>,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        SchedulerConfig {
+            enabled: true,
+            lock_prefix: "scheduler:".into(),
+            jobs: HashMap::new(),
+        }
+    }
+}
+
+/// 单个任务的配置，未配置的项使用代码中声明的值
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct JobConfig {
+    pub enabled: Option<bool>,
+    /// cron 表达式，优先于 `interval_ms`
+    pub cron: Option<String>,
+    pub interval_ms: Option<u64>,
+    /// 是否通过 Redis 锁保证同一时刻仅有一个副本执行
+    pub lock: Option<bool>,
+    pub lock_ttl_ms: Option<u64>,
+}
@@ -0,0 +1,14 @@
+#[macro_use]
+extern crate log;
+
+pub mod config;
+pub mod schedule;
+pub mod scheduler;
+#[cfg(feature = "redis")]
+pub mod lock;
+
+pub mod prelude {
+    pub use crate::config;
+    pub use crate::schedule::Schedule;
+    pub use crate::scheduler::{Job, Scheduler};
+}
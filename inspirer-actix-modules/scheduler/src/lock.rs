@@ -0,0 +1,63 @@
+//! 基于 Redis 的任务锁
+//!
+//! 多个副本在同一触发时间竞争同一个键（`{prefix}{job}:{触发时间戳}`），仅成功写入的副本执行任务。
+//! 锁不会在任务完成后主动释放，而是在有效期后过期，因此有效期只需覆盖副本之间的时钟偏差。
+
+use std::process;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use redis::aio::ConnectionLike;
+use redis::RedisResult;
+
+#[derive(Debug, Clone)]
+pub struct JobLock {
+    prefix: String,
+    ttl: Duration,
+}
+
+impl JobLock {
+    pub fn new(prefix: &str, ttl: Duration) -> Self {
+        JobLock { prefix: prefix.to_string(), ttl }
+    }
+
+    pub fn key(&self, job: &str, fire_at: &DateTime<Utc>) -> String {
+        format!("{}{}:{}", self.prefix, job, fire_at.timestamp_millis())
+    }
+
+    /// 尝试获取任务在 `fire_at` 触发时的执行权
+    pub async fn acquire<C: ConnectionLike>(&self, conn: &mut C, job: &str, fire_at: &DateTime<Utc>) -> RedisResult<bool> {
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(self.key(job, fire_at))
+            .arg(process::id())
+            .arg("NX")
+            .arg("PX")
+            .arg(self.ttl.as_millis() as u64)
+            .query_async(conn)
+            .await?;
+
+        Ok(acquired.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 需要本地运行 redis-server，可通过 `REDIS_URL` 指定地址：
+    /// `cargo test --features redis -- --ignored`
+    #[tokio::test]
+    #[ignore]
+    async fn test_acquire() {
+        let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".into());
+        let client = redis::Client::open(url).unwrap();
+        let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+
+        let lock = JobLock::new("scheduler:test:", Duration::from_secs(5));
+        let fire_at = Utc::now();
+
+        assert!(lock.acquire(&mut conn, "cleanup", &fire_at).await.unwrap());
+        assert!(!lock.acquire(&mut conn, "cleanup", &fire_at).await.unwrap());
+        assert!(lock.acquire(&mut conn, "report", &fire_at).await.unwrap());
+    }
+}
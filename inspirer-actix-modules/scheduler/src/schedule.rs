@@ -0,0 +1,58 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+/// 任务调度规则
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    /// cron 表达式，包含秒字段，例如 `0 */5 * * * *`
+    Cron(Box<cron::Schedule>),
+    /// 固定间隔
+    Interval(Duration),
+}
+
+impl Schedule {
+    pub fn cron(expression: &str) -> Result<Self, cron::error::Error> {
+        cron::Schedule::from_str(expression).map(|schedule| Schedule::Cron(Box::new(schedule)))
+    }
+
+    pub fn interval(interval: Duration) -> Self {
+        Schedule::Interval(interval)
+    }
+
+    /// 晚于 `now` 的下一次触发时间
+    ///
+    /// 固定间隔按 Unix 纪元对齐，使多个副本的触发时间保持一致。
+    pub fn next_after(&self, now: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::Cron(schedule) => schedule.after(now).next(),
+            Schedule::Interval(interval) => {
+                let interval = (interval.as_millis() as i64).max(1);
+                let next = (now.timestamp_millis().div_euclid(interval) + 1) * interval;
+                Utc.timestamp_millis_opt(next).single()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_after() {
+        let now = Utc.timestamp_millis_opt(1_600_000_012_345).unwrap();
+
+        let schedule = Schedule::cron("0 */5 * * * *").unwrap();
+        assert_eq!(Utc.timestamp_millis_opt(1_600_000_200_000).single(), schedule.next_after(&now));
+
+        let schedule = Schedule::interval(Duration::from_secs(10));
+        assert_eq!(Utc.timestamp_millis_opt(1_600_000_020_000).single(), schedule.next_after(&now));
+
+        let now = Utc.timestamp_millis_opt(1_600_000_020_000).unwrap();
+        assert_eq!(Utc.timestamp_millis_opt(1_600_000_030_000).single(), schedule.next_after(&now));
+
+        assert!(Schedule::cron("every minute").is_err());
+    }
+}
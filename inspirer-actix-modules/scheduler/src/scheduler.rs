@@ -0,0 +1,403 @@
+//! 定时任务调度
+//!
+//! 定时任务以 cron 表达式或固定间隔声明，注册为 [`BackgroundTask`]，随应用服务启动与停止：
+//!
+//! ```ignore
+//! Scheduler::new()
+//!     .job(Job::new("cleanup", |ctx: TaskContext| async move {
+//!         let service = ctx.container().service::<_, CleanupService>()?;
+//!         service.cleanup().await
+//!     }).schedule(Schedule::cron("0 0 3 * * *")?))
+//!     .job(Job::new("warmup", warmup).every(Duration::from_secs(60)))
+//!     .register(&mut module_provider)?;
+//! ```
+//!
+//! 任务的调度规则可通过 `Config` 模块中的 `scheduler` 配置节覆盖（任务名称需为小写）：
+//!
+//! ```toml
+//! [scheduler]
+//! enabled = true
+//!
+//! [scheduler.jobs.cleanup]
+//! cron = "0 30 4 * * *"
+//! lock = true
+//! lock_ttl_ms = 60000
+//!
+//! [scheduler.jobs.warmup]
+//! enabled = false
+//! ```
+//!
+//! 启用 `redis` 特性后，任务可以通过 Redis 锁保证每次触发仅有一个副本执行，详见 [`lock`](crate::lock) 模块。
+//! 单次执行失败仅记录日志，不影响后续调度。
+
+use std::any::type_name;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::future::{Either, FutureExt, LocalBoxFuture, select};
+use futures_timer::Delay;
+use inspirer_actix_ext_core::config::{Config, ConfigError};
+use inspirer_actix_ext_core::error::BootstrapError;
+use inspirer_actix_ext_core::module::ModuleProvider;
+use inspirer_actix_ext_core::task::{BackgroundTask, TaskContext};
+
+#[cfg(feature = "redis")]
+use redis::aio::MultiplexedConnection;
+
+use crate::config::{JobConfig, SchedulerConfig};
+#[cfg(feature = "redis")]
+use crate::lock::JobLock;
+use crate::schedule::Schedule;
+
+type JobFn = Box<dyn Fn(TaskContext) -> LocalBoxFuture<'static, anyhow::Result<()>> + Send + Sync>;
+
+#[cfg(feature = "redis")]
+const DEFAULT_LOCK_TTL: Duration = Duration::from_secs(60);
+
+/// 定时任务
+pub struct Job {
+    name: String,
+    schedule: Option<Schedule>,
+    run: JobFn,
+    #[cfg(feature = "redis")]
+    lock_ttl: Option<Duration>,
+}
+
+impl Job {
+    pub fn new<F, R>(name: &str, job: F) -> Self
+        where F: Fn(TaskContext) -> R + Send + Sync + 'static,
+              R: Future<Output=anyhow::Result<()>> + 'static
+    {
+        Job {
+            name: name.to_string(),
+            schedule: None,
+            run: Box::new(move |ctx| job(ctx).boxed_local()),
+            #[cfg(feature = "redis")]
+            lock_ttl: None,
+        }
+    }
+
+    pub fn schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// 按固定间隔执行
+    pub fn every(self, interval: Duration) -> Self {
+        self.schedule(Schedule::interval(interval))
+    }
+
+    /// 通过 Redis 锁保证每次触发仅有一个副本执行，`ttl` 为锁的有效期
+    #[cfg(feature = "redis")]
+    pub fn lock(mut self, ttl: Duration) -> Self {
+        self.lock_ttl = Some(ttl);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn display_name(&self) -> String {
+        format!("{}({})", type_name::<Job>(), self.name)
+    }
+}
+
+/// 定时任务调度器
+#[derive(Default)]
+pub struct Scheduler {
+    jobs: Vec<Job>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn job(mut self, job: Job) -> Self {
+        self.jobs.push(job);
+        self
+    }
+
+    /// 按 `scheduler` 配置节将定时任务注册为后台任务
+    ///
+    /// 需要在 `Config` 模块之后注册，使用锁的任务还需要在 Redis 多路复用连接模块之后注册。
+    pub fn register(self, provider: &mut ModuleProvider) -> Result<(), BootstrapError> {
+        let config = scheduler_config(provider)?;
+        if !config.enabled {
+            info!("Scheduler is disabled, skip registration.");
+            return Ok(());
+        }
+
+        for job in self.jobs {
+            let job_config = config.jobs.get(&job.name)
+                .or_else(|| config.jobs.get(&job.name.to_lowercase()))
+                .cloned()
+                .unwrap_or_default();
+
+            if job_config.enabled == Some(false) {
+                info!("Scheduled job [{}] is disabled, skip registration.", job.name);
+                continue;
+            }
+
+            #[cfg(not(feature = "redis"))]
+            check_lock(&job, &job_config)?;
+
+            let runner = Arc::new(JobRunner {
+                schedule: job_schedule(&job, &job_config)?,
+                #[cfg(feature = "redis")]
+                lock: job_lock(provider, &config, &job, &job_config)?,
+                name: job.name,
+                run: job.run,
+            });
+
+            info!("Register scheduled job [{}].", runner.name);
+            provider.register_task(BackgroundTask::new(&runner.name.clone(), move |ctx| {
+                let runner = runner.clone();
+                async move { runner.run(ctx).await }
+            }));
+        }
+
+        Ok(())
+    }
+}
+
+fn scheduler_config(provider: &ModuleProvider) -> Result<SchedulerConfig, BootstrapError> {
+    if !provider.contains::<Config>() {
+        return Ok(SchedulerConfig::default());
+    }
+
+    match provider.config_section::<Scheduler, SchedulerConfig>("scheduler") {
+        Err(BootstrapError::ConfigMissing { .. }) => Ok(SchedulerConfig::default()),
+        result => result,
+    }
+}
+
+fn job_schedule(job: &Job, config: &JobConfig) -> Result<Schedule, BootstrapError> {
+    if let Some(expression) = config.cron.as_deref() {
+        return Schedule::cron(expression).map_err(|err| BootstrapError::ConfigInvalid {
+            module: job.display_name(),
+            key: format!("scheduler.jobs.{}.cron", job.name),
+            source: Box::new(ConfigError::Message(err.to_string())),
+        });
+    }
+
+    if let Some(interval) = config.interval_ms {
+        return Ok(Schedule::interval(Duration::from_millis(interval)));
+    }
+
+    job.schedule.clone().ok_or_else(|| BootstrapError::ConfigMissing {
+        module: job.display_name(),
+        key: format!("scheduler.jobs.{}.cron", job.name),
+    })
+}
+
+#[cfg(feature = "redis")]
+fn job_lock(provider: &ModuleProvider, scheduler: &SchedulerConfig, job: &Job, config: &JobConfig) -> Result<Option<JobLock>, BootstrapError> {
+    if !config.lock.unwrap_or_else(|| job.lock_ttl.is_some()) {
+        return Ok(None);
+    }
+
+    if !provider.contains::<MultiplexedConnection>() {
+        return Err(BootstrapError::MissingDependency {
+            module: job.display_name(),
+            dependency: type_name::<MultiplexedConnection>().to_string(),
+        });
+    }
+
+    let ttl = config.lock_ttl_ms.map(Duration::from_millis)
+        .or(job.lock_ttl)
+        .unwrap_or(DEFAULT_LOCK_TTL);
+
+    Ok(Some(JobLock::new(&scheduler.lock_prefix, ttl)))
+}
+
+#[cfg(not(feature = "redis"))]
+fn check_lock(job: &Job, config: &JobConfig) -> Result<(), BootstrapError> {
+    match config.lock {
+        Some(true) => Err(BootstrapError::ConfigInvalid {
+            module: job.display_name(),
+            key: format!("scheduler.jobs.{}.lock", job.name),
+            source: Box::new(ConfigError::Message("job lock requires the `redis` feature".into())),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// 下一次触发时间，晚于当前时间及上一次触发时间
+///
+/// 定时器可能略早于触发时间唤醒，此时当前时间仍早于上一次触发时间，若只按当前时间计算将重复触发。
+fn next_fire_at(schedule: &Schedule, now: DateTime<Utc>, last_fire_at: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    schedule.next_after(&last_fire_at.map_or(now, |last_fire_at| last_fire_at.max(now)))
+}
+
+struct JobRunner {
+    name: String,
+    schedule: Schedule,
+    run: JobFn,
+    #[cfg(feature = "redis")]
+    lock: Option<JobLock>,
+}
+
+impl JobRunner {
+    async fn run(&self, ctx: TaskContext) -> anyhow::Result<()> {
+        let mut last_fire_at = None;
+        loop {
+            let fire_at = match next_fire_at(&self.schedule, Utc::now(), last_fire_at) {
+                Some(fire_at) => fire_at,
+                None => {
+                    info!("Scheduled job [{}] has no upcoming fire time.", self.name);
+                    return Ok(());
+                }
+            };
+
+            let delay = (fire_at - Utc::now()).to_std().unwrap_or_default();
+            if let Either::Left(_) = select(Box::pin(ctx.cancelled()), Delay::new(delay)).await {
+                return Ok(());
+            }
+            last_fire_at = Some(fire_at);
+
+            if !self.acquire(&ctx, &fire_at).await {
+                continue;
+            }
+
+            debug!("Run scheduled job [{}].", self.name);
+            if let Err(err) = (self.run)(ctx.clone()).await {
+                error!("Scheduled job [{}] failed: {:#}", self.name, err);
+            }
+        }
+    }
+
+    #[cfg(feature = "redis")]
+    async fn acquire(&self, ctx: &TaskContext, fire_at: &DateTime<Utc>) -> bool {
+        let lock = match &self.lock {
+            Some(lock) => lock,
+            None => return true,
+        };
+
+        let mut conn = match ctx.container().get::<MultiplexedConnection>() {
+            Some(conn) => conn,
+            None => {
+                error!("Redis connection is not registered, skip scheduled job [{}].", self.name);
+                return false;
+            }
+        };
+
+        match lock.acquire(&mut conn, &self.name, fire_at).await {
+            Ok(true) => true,
+            Ok(false) => {
+                debug!("Scheduled job [{}] is running on another instance.", self.name);
+                false
+            }
+            Err(err) => {
+                warn!("Failed to acquire lock of scheduled job [{}], skip: {}", self.name, err);
+                false
+            }
+        }
+    }
+
+    #[cfg(not(feature = "redis"))]
+    async fn acquire(&self, _ctx: &TaskContext, _fire_at: &DateTime<Utc>) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use inspirer_actix_ext_core::task::TaskState;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_interval_job() {
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert(7usize);
+        Scheduler::new()
+            .job(Job::new("tick", |ctx: TaskContext| async move {
+                assert_eq!(Some(7), ctx.container().get::<usize>());
+                RUNS.fetch_add(1, Ordering::SeqCst);
+                anyhow::bail!("failure does not stop the schedule")
+            }).every(Duration::from_millis(10)))
+            .register(&mut module_provider)
+            .unwrap();
+
+        let container = module_provider.into_module_container();
+        container.start_tasks();
+
+        for _ in 0..100 {
+            if RUNS.load(Ordering::SeqCst) >= 2 {
+                break;
+            }
+            Delay::new(Duration::from_millis(10)).await;
+        }
+        assert!(RUNS.load(Ordering::SeqCst) >= 2);
+
+        container.shutdown().await;
+        assert_eq!(TaskState::Cancelled, container.tasks()[0].state);
+    }
+
+    #[test]
+    fn test_next_fire_at() {
+        use chrono::TimeZone;
+
+        let schedule = Schedule::interval(Duration::from_secs(10));
+        let now = Utc.timestamp_millis_opt(1_600_000_019_995).unwrap();
+        let last_fire_at = Utc.timestamp_millis_opt(1_600_000_020_000).unwrap();
+
+        assert_eq!(Some(last_fire_at), next_fire_at(&schedule, now, None));
+        assert_eq!(Utc.timestamp_millis_opt(1_600_000_030_000).single(), next_fire_at(&schedule, now, Some(last_fire_at)));
+
+        let now = Utc.timestamp_millis_opt(1_600_000_045_000).unwrap();
+        assert_eq!(Utc.timestamp_millis_opt(1_600_000_050_000).single(), next_fire_at(&schedule, now, Some(last_fire_at)));
+    }
+
+    fn provider(settings: &[(&str, &str)]) -> ModuleProvider {
+        let mut config = Config::new();
+        for (key, value) in settings {
+            config.set(key, *value).unwrap();
+        }
+
+        let mut module_provider = ModuleProvider::new();
+        module_provider.insert(config);
+        module_provider
+    }
+
+    fn job(name: &str) -> Job {
+        Job::new(name, |_: TaskContext| async { Ok(()) })
+    }
+
+    #[test]
+    fn test_config() {
+        let mut module_provider = provider(&[
+            ("scheduler.jobs.cleanup.enabled", "false"),
+            ("scheduler.jobs.report.interval_ms", "1000"),
+        ]);
+
+        Scheduler::new()
+            .job(job("cleanup").every(Duration::from_secs(1)))
+            .job(job("report"))
+            .register(&mut module_provider)
+            .unwrap();
+
+        let tasks = module_provider.into_module_container().tasks();
+        assert_eq!(vec!["report"], tasks.iter().map(|task| task.name.as_str()).collect::<Vec<_>>());
+
+        let err = Scheduler::new().job(job("report")).register(&mut ModuleProvider::new()).unwrap_err();
+        assert!(matches!(err, BootstrapError::ConfigMissing { ref key, .. } if key == "scheduler.jobs.report.cron"));
+
+        let err = Scheduler::new().job(job("report"))
+            .register(&mut provider(&[("scheduler.jobs.report.cron", "every minute")]))
+            .unwrap_err();
+        assert!(matches!(err, BootstrapError::ConfigInvalid { .. }));
+
+        let mut module_provider = provider(&[("scheduler.enabled", "false")]);
+        Scheduler::new().job(job("report")).register(&mut module_provider).unwrap();
+        assert!(module_provider.into_module_container().tasks().is_empty());
+    }
+}
@@ -0,0 +1,473 @@
+//! 应用启动器
+//!
+//! 将配置加载、模块注册与 `HttpServer` 的创建串联起来，免去每个项目重复编写的启动代码：
+//!
+//! ```no_run
+//! use inspirer_actix_ext::application::Application;
+//! use inspirer_actix_ext::config::ConfigLayers;
+//! use actix_web::{web, HttpResponse};
+//!
+//! #[actix_web::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     Application::new()
+//!         .layered_config(ConfigLayers::new("config").env("APP"))
+//!         .routes(|cfg| {
+//!             cfg.route("/", web::get().to(|| HttpResponse::Ok()));
+//!         })
+//!         .health("/health")
+//!         .run()
+//!         .await
+//! }
+//! ```
+//!
+//! 监听地址、工作线程数及平滑关闭超时读取自 `Config` 模块的 `server` 配置节：
+//!
+//! ```toml
+//! [server]
+//! bind = ["0.0.0.0:8080"]
+//! workers = 4
+//! shutdown_timeout = 30
+//! ```
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::{App, HttpServer};
+use actix_web::web::ServiceConfig;
+use serde::Deserialize;
+
+use inspirer_actix_ext_core::config::{config_provider, ConfigLayers, ConfigProvider, ConfigSection, ConfigWatcher};
+use inspirer_actix_ext_core::error::BootstrapError;
+use inspirer_actix_ext_core::preludes::{ModuleContainer, ModuleDefinition, ModuleProvider};
+use inspirer_actix_ext_core::task::BackgroundTask;
+
+/// HTTP 服务配置，对应 `server` 配置节，未配置时使用默认值
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// 监听地址列表
+    pub bind: Vec<String>,
+    /// 工作线程数，默认为 CPU 核心数
+    pub workers: Option<usize>,
+    /// 平滑关闭超时（秒），默认为 30 秒
+    pub shutdown_timeout: Option<u64>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            bind: vec!["127.0.0.1:8080".into()],
+            workers: None,
+            shutdown_timeout: None,
+        }
+    }
+}
+
+type RouteConfigurator = Arc<dyn Fn(&mut ServiceConfig) + Send + Sync>;
+
+/// 应用启动器
+///
+/// 依次完成：加载配置并注册 `Config` 模块、按依赖顺序注册模块及后台任务、调用模块启动钩子、
+/// 创建并运行 `HttpServer`，服务停止后调用模块关闭钩子。
+pub struct Application {
+    config_layers: Option<ConfigLayers>,
+    config_providers: Vec<ConfigProvider>,
+    config_watch: Option<Duration>,
+    definitions: Vec<ModuleDefinition>,
+    tasks: Vec<BackgroundTask>,
+    routes: Vec<RouteConfigurator>,
+    health: Option<String>,
+    logging: bool,
+}
+
+impl Default for Application {
+    fn default() -> Self {
+        Application::new()
+    }
+}
+
+impl Application {
+    pub fn new() -> Self {
+        Application {
+            config_layers: None,
+            config_providers: vec![],
+            config_watch: None,
+            definitions: vec![],
+            tasks: vec![],
+            routes: vec![],
+            health: None,
+            logging: true,
+        }
+    }
+
+    /// 使用按运行环境分层的配置，当前运行环境将注册为 `Profile` 模块
+    ///
+    /// 通过 [`Application::config`] 添加的配置来源在分层配置之后合并。
+    pub fn layered_config(mut self, layers: ConfigLayers) -> Self {
+        self.config_layers = Some(layers);
+        self
+    }
+
+    /// 添加配置来源，按添加顺序合并，后添加的覆盖先添加的
+    pub fn config(mut self, provider: ConfigProvider) -> Self {
+        self.config_providers.push(provider);
+        self
+    }
+
+    /// 监听配置文件，变化时重新加载配置，参见 [`ConfigWatcher`]
+    pub fn watch_config(mut self, interval: Duration) -> Self {
+        self.config_watch = Some(interval);
+        self
+    }
+
+    /// 添加配置节模块，参见 [`ConfigSection`]
+    pub fn config_section<C: ConfigSection>(mut self) -> Self {
+        self.definitions.push(C::definition());
+        self
+    }
+
+    /// 添加模块定义
+    ///
+    /// 全部模块定义在配置加载后通过 [`ModuleProvider::register_all`] 注册，执行任何工厂方法之前将集中执行配置预检。
+    pub fn module(mut self, definition: ModuleDefinition) -> Self {
+        self.definitions.push(definition);
+        self
+    }
+
+    /// 批量添加模块定义
+    pub fn modules(mut self, definitions: Vec<ModuleDefinition>) -> Self {
+        self.definitions.extend(definitions);
+        self
+    }
+
+    /// 添加后台任务，在 HTTP 服务启动后运行
+    pub fn task(mut self, task: BackgroundTask) -> Self {
+        self.tasks.push(task);
+        self
+    }
+
+    /// 添加路由配置
+    pub fn routes<F>(mut self, configure: F) -> Self
+        where F: Fn(&mut ServiceConfig) + Send + Sync + 'static
+    {
+        self.routes.push(Arc::new(configure));
+        self
+    }
+
+    /// 在指定路径挂载健康检查服务，参见 [`ModuleContainer::health_service`]
+    pub fn health(mut self, path: &str) -> Self {
+        self.health = Some(path.to_string());
+        self
+    }
+
+    /// 不初始化日志，适用于已自行初始化日志的场景
+    pub fn without_logging(mut self) -> Self {
+        self.logging = false;
+        self
+    }
+
+    /// 加载配置并注册全部模块，返回模块容器
+    ///
+    /// 适用于测试及命令行等不需要启动 HTTP 服务的场景。
+    pub async fn build(self) -> Result<ModuleContainer, BootstrapError> {
+        let (container, _) = self.bootstrap().await?;
+        Ok(container)
+    }
+
+    async fn bootstrap(self) -> Result<(ModuleContainer, ServerConfig), BootstrapError> {
+        let mut provider = ModuleProvider::new();
+        let mut config_providers = vec![];
+
+        if let Some(layers) = &self.config_layers {
+            config_providers.extend(provider.activate_profile(layers));
+        }
+        config_providers.extend(self.config_providers);
+
+        if !config_providers.is_empty() {
+            match self.config_watch {
+                Some(interval) => {
+                    provider.register_watched_config(ConfigWatcher::new(config_providers).interval(interval)).await?;
+                }
+                None => provider.register(config_provider(config_providers)).await?,
+            }
+        }
+
+        provider.register_all(self.definitions).await?;
+
+        for task in self.tasks {
+            provider.register_task(task);
+        }
+
+        let server_config = server_config(&provider)?;
+        Ok((provider.into_module_container(), server_config))
+    }
+
+    /// 启动应用并运行 HTTP 服务，直至收到停止信号并完成平滑关闭
+    ///
+    /// 日志默认以 `info` 级别初始化，可通过 `RUST_LOG` 环境变量调整。
+    pub async fn run(self) -> anyhow::Result<()> {
+        if self.logging {
+            let _ = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+                .try_init();
+        }
+
+        let routes = self.routes.clone();
+        let health = self.health.clone();
+        let (container, server_config) = self.bootstrap().await?;
+
+        info!("{}", container.startup_report());
+        // 启动失败时已启动的模块由 `start` 负责关闭
+        container.start().await?;
+
+        let app_container = container.clone();
+        let mut server = HttpServer::new(move || {
+            let mut app = App::new()
+                .configure(app_container.module_provider());
+
+            if let Some(path) = health.as_deref() {
+                app = app.service(app_container.health_service(path));
+            }
+
+            routes.iter()
+                .fold(app, |app, configure| app.configure(|cfg| configure(cfg)))
+        });
+
+        if let Some(workers) = server_config.workers {
+            server = server.workers(workers);
+        }
+
+        if let Some(timeout) = server_config.shutdown_timeout {
+            server = server.shutdown_timeout(timeout);
+        }
+
+        for addr in server_config.bind.iter() {
+            info!("Listening on {}.", addr);
+            server = match server.bind(addr) {
+                Ok(server) => server,
+                Err(err) => {
+                    container.shutdown().await;
+                    return Err(anyhow::Error::new(err).context(format!("Failed to bind {}", addr)));
+                }
+            };
+        }
+
+        container.serve(server.run()).await
+    }
+}
+
+fn server_config(provider: &ModuleProvider) -> Result<ServerConfig, BootstrapError> {
+    if provider.get_ref::<inspirer_actix_ext_core::config::Config>().is_none() {
+        return Ok(ServerConfig::default());
+    }
+
+    match provider.config_section::<Application, ServerConfig>("server") {
+        Err(BootstrapError::ConfigMissing { .. }) => Ok(ServerConfig::default()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use inspirer_actix_ext_core::config::{Config, Profile, Validate, ValidationError, ValidationErrors};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Greeting(String);
+
+    async fn greeting(provider: &ModuleProvider) -> Result<Greeting, BootstrapError> {
+        let config = provider.get::<Config>().unwrap();
+        let name = config.get_str("greeting.name")
+            .map_err(BootstrapError::factory_failed::<Greeting, _>)?;
+
+        Ok(Greeting(format!("hello, {}", name)))
+    }
+
+    #[actix_rt::test]
+    async fn test_build() {
+        let path = crate::tests::temp_dir("application").join("config.toml");
+        std::fs::write(&path, "[greeting]\nname = \"world\"\n[server]\nworkers = 2\n").unwrap();
+
+        let application = Application::new()
+            .config(ConfigProvider::Path(path))
+            .module(ModuleDefinition::new(greeting).after::<Config>());
+
+        let (container, server_config) = application.bootstrap().await.unwrap();
+
+        assert_eq!(Some(Greeting("hello, world".into())), container.get::<Greeting>());
+        assert!(container.get::<Config>().is_some());
+        assert_eq!(server_config.workers, Some(2));
+        assert_eq!(server_config.bind, vec!["127.0.0.1:8080".to_string()]);
+    }
+
+    #[derive(Deserialize, Clone, Debug, PartialEq, ConfigSection)]
+    #[config(key = "mail")]
+    struct MailConfig {
+        host: String,
+        #[serde(default)]
+        port: u16,
+    }
+
+    #[derive(Deserialize, Clone, Debug, Default, PartialEq, ConfigSection)]
+    #[config(key = "limit", default)]
+    struct RateLimitConfig {
+        rate: u32,
+    }
+
+    #[actix_rt::test]
+    async fn test_config_section_derive() {
+        let path = crate::tests::temp_dir("application-section").join("config.toml");
+        std::fs::write(&path, "[mail]\nhost = \"smtp.example.com\"\n").unwrap();
+
+        let container = Application::new()
+            .modules(vec![MailConfig::definition(), RateLimitConfig::definition()])
+            .config(ConfigProvider::Path(path))
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!("mail", MailConfig::KEY);
+        assert_eq!(Some(MailConfig { host: "smtp.example.com".into(), port: 0 }), container.get::<MailConfig>());
+        assert_eq!(Some(RateLimitConfig::default()), container.get::<RateLimitConfig>());
+
+        let err = Application::new().module(MailConfig::definition()).build().await.err().unwrap();
+        assert!(err.to_string().ends_with("requires config [mail], but it is missing"));
+    }
+
+    #[derive(Deserialize, Clone, ConfigSection)]
+    #[config(key = "cache", validate)]
+    struct CacheConfig {
+        capacity: usize,
+    }
+
+    impl Validate for CacheConfig {
+        fn validate(&self) -> Result<(), ValidationErrors> {
+            let mut errors = ValidationErrors::new();
+            if self.capacity == 0 {
+                errors.add("capacity", ValidationError::new("range"));
+            }
+
+            if errors.is_empty() { Ok(()) } else { Err(errors) }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_config_section_validation() {
+        static CONNECTS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+        let path = crate::tests::temp_dir("application-validation").join("config.toml");
+        std::fs::write(&path, "[cache]\ncapacity = 0\n").unwrap();
+
+        let connect = |_: &ModuleProvider| async {
+            CONNECTS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok::<_, BootstrapError>(Greeting("connected".into()))
+        };
+        let err = Application::new()
+            .config(ConfigProvider::Path(path.clone()))
+            .module(ModuleDefinition::new(connect))
+            .config_section::<CacheConfig>()
+            .config_section::<MailConfig>()
+            .build()
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(0, CONNECTS.load(std::sync::atomic::Ordering::SeqCst));
+        match err {
+            BootstrapError::ModulesInitFailed(failures) => {
+                assert_eq!(2, failures.len());
+                assert!(failures[0].to_string().ends_with("has 1 config violation(s): [cache.capacity] range"));
+                assert!(failures[1].to_string().ends_with("requires config [mail], but it is missing"));
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+
+        std::fs::write(&path, "[cache]\ncapacity = 64\n").unwrap();
+        let container = Application::new()
+            .config(ConfigProvider::Path(path))
+            .config_section::<CacheConfig>()
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(Some(64), container.get::<CacheConfig>().map(|cache| cache.capacity));
+    }
+
+    #[actix_rt::test]
+    async fn test_definition_config_check() {
+        static CONNECTS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+        let path = crate::tests::temp_dir("application-config-check").join("config.toml");
+        std::fs::write(&path, "[cache]\ncapacity = 0\n").unwrap();
+
+        let connect = |_: &ModuleProvider| async {
+            CONNECTS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok::<_, BootstrapError>(Greeting("connected".into()))
+        };
+        let err = Application::new()
+            .config(ConfigProvider::Path(path))
+            .module(ModuleDefinition::new(connect))
+            .module(ModuleDefinition::new(connect).validate_config::<Greeting, CacheConfig>(CacheConfig::KEY))
+            .build()
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(0, CONNECTS.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(err.to_string().ends_with("has 1 config violation(s): [cache.capacity] range"));
+    }
+
+    #[actix_rt::test]
+    async fn test_bind_failure_shutdown_modules() {
+        use inspirer_actix_ext_core::lifecycle::ModuleHooks;
+
+        static SHUTDOWNS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+        let path = crate::tests::temp_dir("application-bind").join("config.toml");
+        std::fs::write(&path, "[greeting]\nname = \"world\"\n[server]\nbind = [\"not-an-address\"]\n").unwrap();
+
+        let hooks = ModuleHooks::new().on_shutdown(|_| async {
+            SHUTDOWNS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        });
+        let err = Application::new()
+            .without_logging()
+            .config(ConfigProvider::Path(path))
+            .module(ModuleDefinition::with_hooks(greeting, hooks).after::<Config>())
+            .run()
+            .await
+            .unwrap_err();
+
+        assert_eq!("Failed to bind not-an-address", err.to_string());
+        assert_eq!(1, SHUTDOWNS.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[actix_rt::test]
+    async fn test_build_without_config() {
+        let (container, server_config) = Application::new().bootstrap().await.unwrap();
+
+        assert!(container.modules().is_empty());
+        assert_eq!(server_config, ServerConfig::default());
+    }
+
+    #[actix_rt::test]
+    async fn test_layered_config() {
+        let dir = crate::tests::temp_dir("application-layers");
+        std::fs::write(dir.join("default.toml"), "[server]\nworkers = 1\n").unwrap();
+        std::fs::write(dir.join("staging.toml"), "[server]\nworkers = 4\n").unwrap();
+
+        std::env::set_var("APPLICATION_LAYERS_TEST_PROFILE", "staging");
+        let application = Application::new()
+            .layered_config(ConfigLayers::new(&dir).profile_var("APPLICATION_LAYERS_TEST_PROFILE"));
+
+        let (container, server_config) = application.bootstrap().await.unwrap();
+        assert_eq!(Some(4), server_config.workers);
+        assert_eq!(Some(Profile::new("staging")), container.get::<Profile>());
+
+        let application = Application::new()
+            .layered_config(ConfigLayers::new(&dir).profile_var("APPLICATION_LAYERS_TEST_PROFILE"))
+            .watch_config(Duration::from_secs(1));
+
+        let container = application.build().await.unwrap();
+        let config = container.reloadable::<Config>().unwrap();
+        assert_eq!(4, config.get().get_int("server.workers").unwrap());
+        assert_eq!(1, container.tasks().len());
+    }
+}
@@ -1,10 +1,21 @@
 #[macro_use]
 extern crate inspirer_actix_ext_derive;
+extern crate self as inspirer_actix_ext;
+#[macro_use]
+extern crate log;
+
+pub mod application;
+
+pub use application::Application;
 
-pub use inspirer_actix_ext_core::preludes::{config, service, ModuleProvider, ModuleContainer, ModuleFactoryFn};
-pub use inspirer_actix_ext_core::error;
+pub use inspirer_actix_ext_core::preludes::{config, service, ModuleProvider, ModuleContainer, ModuleFactoryFn, ModuleDefinition, ModuleHooks};
+pub use inspirer_actix_ext_core::qualifier;
+pub use inspirer_actix_ext_core::{condition, error, event, feature, health, inspect, interface, lazy, placeholder, reload, retry, scope, task};
 pub use inspirer_actix_ext_derive::*;
 
+#[cfg(feature = "testing")]
+pub use inspirer_actix_ext_core::testing;
+
 #[cfg(feature = "validator")]
 pub mod validator {
     pub use inspirer_actix_validator::*;
@@ -20,8 +31,29 @@ pub mod redis {
     pub use inspirer_actix_module_redis::prelude::*;
 }
 
+#[cfg(feature = "scheduler")]
+pub mod scheduler {
+    pub use inspirer_actix_module_scheduler::prelude::*;
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// 创建测试独占的临时目录，避免同时运行的测试互相覆盖文件
+    pub(crate) fn temp_dir(name: &str) -> PathBuf {
+        static SEQUENCE: AtomicUsize = AtomicUsize::new(0);
+
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let dir = std::env::temp_dir().join(format!(
+            "inspirer-{}-{}-{}-{}", name, std::process::id(), nanos, SEQUENCE.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);